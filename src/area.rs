@@ -0,0 +1,65 @@
+use crate::distance::{rect_area, EARTH_RADIUS};
+use crate::{decode_bbox, CellSet, GeohashError};
+use libm::cos;
+
+/// The area of the cell of a geohash in square meters, on a spherical earth.
+///
+/// Cells of the same length span the same angles, but their area shrinks with the cosine of
+/// the latitude towards the poles. This is the exact area of the cell, bounded by two meridians
+/// and two parallels.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::area_m2;
+///
+/// let equator = area_m2("s00").unwrap();
+/// let paris = area_m2("u09").unwrap();
+/// assert!((equator / 1e6 - 24_448.5).abs() < 0.1);
+/// assert!(paris < 0.7 * equator);
+/// ```
+pub fn area_m2(hash_str: &str) -> Result<f64, GeohashError> {
+    Ok(rect_area(decode_bbox(hash_str)?))
+}
+
+/// The perimeter of the cell of a geohash in meters, on a spherical earth.
+///
+/// The east and west edges are along meridians, and the north and south edges are along
+/// parallels, which are shorter towards the poles.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::perimeter_m;
+///
+/// assert!((perimeter_m("s00").unwrap() / 1e3 - 625.4).abs() < 0.1);
+/// ```
+pub fn perimeter_m(hash_str: &str) -> Result<f64, GeohashError> {
+    let rect = decode_bbox(hash_str)?;
+    let (min, max) = (rect.min(), rect.max());
+    let meridian = (max.y - min.y).to_radians();
+    let parallel = (max.x - min.x).to_radians();
+    Ok(EARTH_RADIUS
+        * (2.0 * meridian + parallel * (cos(min.y.to_radians()) + cos(max.y.to_radians()))))
+}
+
+/// The area covered by the cells of a collection of geohashes in square meters, on a
+/// spherical earth.
+///
+/// Overlapping cells, such as a cell and one of its children, are only counted once.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{area_m2, total_area_m2};
+///
+/// let total = total_area_m2(["u09", "u09tun", "u0d"]).unwrap();
+/// assert!((total - area_m2("u09").unwrap() - area_m2("u0d").unwrap()).abs() < 1e-3);
+/// ```
+pub fn total_area_m2<I, S>(hashes: I) -> Result<f64, GeohashError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    Ok(CellSet::from_hashes(hashes)?.rects().map(rect_area).sum())
+}
@@ -0,0 +1,200 @@
+use crate::core::{bbox_int_with_precision, hash_to_int, int_to_hash};
+use crate::distance::rect_area;
+use crate::geometry::in_range;
+use crate::ranges::merge;
+use crate::{encode_int, Coord, GeohashError, Rect};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+// the number of bits of the longest geohashes, every cell is a range of hashes of that many
+// bits
+const BITS: u32 = 60;
+
+/// A region made of geohash cells of any length, with set operations.
+///
+/// The region is stored as the sorted ranges of the 60 bit integer hashes (the hashes of the
+/// longest geohashes) of its points, so cells of different lengths covering the same area give
+/// the same set, and ranges never overlap or touch. [`cells`](CellSet::cells) gives back the
+/// fewest cells covering it.
+///
+/// The set operations are linear-time merges over these ranges, which follow the Z-order of
+/// the cells.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{CellSet, Coord};
+///
+/// let zone = CellSet::from_hashes(["u09", "u0d"]).unwrap();
+/// let closed = CellSet::from_hashes(["u09t", "u09w"]).unwrap();
+///
+/// let open = zone.difference(&closed);
+/// assert!(open.contains_point(Coord { x: 2.0, y: 48.5 }));
+/// assert!(!open.contains_point(Coord { x: 2.2945, y: 48.8582 }));
+/// assert_eq!(open.cells().len(), 30 + 1);
+///
+/// let restored = open.union(&closed);
+/// assert_eq!(restored, zone);
+/// assert_eq!(restored.cells(), ["u09", "u0d"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CellSet {
+    ranges: Vec<Range<u64>>,
+}
+
+impl CellSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        CellSet { ranges: Vec::new() }
+    }
+
+    /// Create the set covered by the given geohashes.
+    pub fn from_hashes<I, S>(hashes: I) -> Result<Self, GeohashError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let ranges = hashes
+            .into_iter()
+            .map(|hash| to_range(hash.as_ref()))
+            .collect::<Result<_, _>>()?;
+        Ok(CellSet {
+            ranges: merge(ranges),
+        })
+    }
+
+    /// Returns `true` if the set covers nothing.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Add the cell of a geohash to the set.
+    pub fn insert(&mut self, hash_str: &str) -> Result<(), GeohashError> {
+        let cell = CellSet {
+            ranges: Vec::from([to_range(hash_str)?]),
+        };
+        *self = self.union(&cell);
+        Ok(())
+    }
+
+    /// The fewest geohashes covering the set, in Z-order.
+    pub fn cells(&self) -> Vec<String> {
+        self.iter_cells()
+            .map(|(hash, bits)| int_to_hash(hash, bits as usize / 5))
+            .collect()
+    }
+
+    /// The points in either set.
+    pub fn union(&self, other: &CellSet) -> CellSet {
+        self.combine(other, |a, b| a || b)
+    }
+
+    /// The points in both sets.
+    pub fn intersection(&self, other: &CellSet) -> CellSet {
+        self.combine(other, |a, b| a && b)
+    }
+
+    /// The points in this set and not in `other`.
+    pub fn difference(&self, other: &CellSet) -> CellSet {
+        self.combine(other, |a, b| a && !b)
+    }
+
+    /// The points in exactly one of the sets.
+    pub fn symmetric_difference(&self, other: &CellSet) -> CellSet {
+        self.combine(other, |a, b| a != b)
+    }
+
+    /// Whether the coordinate is in one of the cells of the set.
+    pub fn contains_point(&self, c: Coord<f64>) -> bool {
+        if !in_range(c) {
+            return false;
+        }
+        let hash = encode_int(c, BITS).expect("coordinates are checked");
+        // the last range starting at or before the hash
+        let i = self.ranges.partition_point(|r| r.start <= hash);
+        i > 0 && hash < self.ranges[i - 1].end
+    }
+
+    /// The area covered by the set in square kilometers, on a spherical earth.
+    pub fn area_km2(&self) -> f64 {
+        self.rects().map(rect_area).sum::<f64>() / 1e6
+    }
+
+    // the bounding boxes of the cells of the ranges
+    pub(crate) fn rects(&self) -> impl Iterator<Item = Rect<f64>> + '_ {
+        self.iter_cells()
+            .map(|(hash, bits)| bbox_int_with_precision(hash, bits))
+    }
+
+    // the cells of the ranges as `(integer hash, bits)`, each range is split into the
+    // largest cells aligned on its hashes
+    fn iter_cells(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        self.ranges.iter().flat_map(|r| {
+            let mut start = r.start;
+            core::iter::from_fn(move || {
+                if start == r.end {
+                    return None;
+                }
+                let bits = (5..=BITS)
+                    .step_by(5)
+                    .find(|&bits| {
+                        let size = 1 << (BITS - bits);
+                        start % size == 0 && start + size <= r.end
+                    })
+                    .expect("ranges are made of whole cells");
+                let cell = start >> (BITS - bits);
+                start += 1 << (BITS - bits);
+                Some((cell, bits))
+            })
+        })
+    }
+
+    // sweeps over the bounds of the ranges of both sets, keeping the parts where `keep` is
+    // true for their membership in each set
+    fn combine<F: Fn(bool, bool) -> bool>(&self, other: &CellSet, keep: F) -> CellSet {
+        let (a, b) = (&self.ranges, &other.ranges);
+        let (mut i, mut j) = (0, 0);
+        let mut pos = 0;
+        let mut ranges: Vec<Range<u64>> = Vec::new();
+        loop {
+            while i < a.len() && a[i].end <= pos {
+                i += 1;
+            }
+            while j < b.len() && b[j].end <= pos {
+                j += 1;
+            }
+            if i == a.len() && j == b.len() {
+                break;
+            }
+            // whether `pos` is in each set, and where that next changes
+            let (in_a, next_a) = bound(a.get(i), pos);
+            let (in_b, next_b) = bound(b.get(j), pos);
+            let next = next_a.min(next_b);
+            if keep(in_a, in_b) {
+                match ranges.last_mut() {
+                    Some(last) if last.end == pos => last.end = next,
+                    _ => ranges.push(pos..next),
+                }
+            }
+            pos = next;
+        }
+        CellSet { ranges }
+    }
+}
+
+// whether `pos` is in the next range not before it, and the next bound of that range
+fn bound(range: Option<&Range<u64>>, pos: u64) -> (bool, u64) {
+    match range {
+        Some(r) if r.start <= pos => (true, r.end),
+        Some(r) => (false, r.start),
+        None => (false, 1 << BITS),
+    }
+}
+
+// the range of 60 bit hashes of the points of a cell
+fn to_range(hash_str: &str) -> Result<Range<u64>, GeohashError> {
+    let hash = hash_to_int(hash_str)?;
+    let shift = BITS - hash_str.len() as u32 * 5;
+    Ok(hash << shift..(hash + 1) << shift)
+}
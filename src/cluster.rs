@@ -0,0 +1,170 @@
+use crate::join::cells_suffice;
+use crate::neighbors::block;
+use crate::{encode, Coord, GeohashError};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// A cluster found by [`cluster_points`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    /// The cells covered by the cluster, ordered by geohash.
+    pub cells: Vec<String>,
+    /// The cells of the cluster along its outline, ordered by geohash. These are the cells with
+    /// a cell around them outside of the cluster, and the cells along the poles.
+    pub outline: Vec<String>,
+    /// The number of points in the cluster.
+    pub count: usize,
+}
+
+/// The result of [`cluster_points`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clustering {
+    /// The cluster of each point, in the order of the points, `None` for noise.
+    pub labels: Vec<Option<usize>>,
+    /// The clusters, indexed by the labels.
+    pub clusters: Vec<Cluster>,
+}
+
+/// Cluster points with a DBSCAN-like algorithm over the cells of geohashes of length `len`.
+///
+/// The points are binned into their cells, and a cell with at least `min_points` points is
+/// dense. Dense cells next to each other, sharing an edge or a corner, are connected into the
+/// same cluster. Cells are next to each other across the antimeridian, but not across the
+/// poles. A cell with fewer points is added to the cluster of the
+/// first dense cell around it, by geohash, and its points are noise if there is none.
+///
+/// This takes near-linear time in the number of points, and [`eps_precision`] gives the
+/// length to use for a DBSCAN `eps` distance. Clusters are numbered in the order of the
+/// geohash of their first cell.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{cluster_points, Coord};
+///
+/// let points = [
+///     Coord { x: 2.2945, y: 48.8582 },
+///     Coord { x: 2.2950, y: 48.8590 },
+///     Coord { x: 2.2948, y: 48.8585 },
+///     Coord { x: 2.3522, y: 48.8566 },
+/// ];
+///
+/// let clustering = cluster_points(points, 6, 2).unwrap();
+/// assert_eq!(clustering.labels, [Some(0), Some(0), Some(0), None]);
+/// assert_eq!(clustering.clusters[0].cells, ["u09tun"]);
+/// assert_eq!(clustering.clusters[0].outline, ["u09tun"]);
+/// assert_eq!(clustering.clusters[0].count, 3);
+/// ```
+pub fn cluster_points<I>(
+    points: I,
+    len: usize,
+    min_points: usize,
+) -> Result<Clustering, GeohashError>
+where
+    I: IntoIterator<Item = Coord<f64>>,
+{
+    if !(1..=12).contains(&len) {
+        return Err(GeohashError::InvalidLength(len));
+    }
+    let mut cells: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut n = 0;
+    for (i, c) in points.into_iter().enumerate() {
+        cells.entry(encode(c, len)?).or_default().push(i);
+        n += 1;
+    }
+    let dense = |hash: &str| cells.get(hash).map_or(0, Vec::len) >= min_points;
+
+    // connect the dense cells, breadth-first from each one not yet in a cluster
+    let mut cluster_of: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut clusters: Vec<Vec<String>> = Vec::new();
+    for hash in cells.keys().filter(|hash| dense(hash)) {
+        if cluster_of.contains_key(hash.as_str()) {
+            continue;
+        }
+        let id = clusters.len();
+        cluster_of.insert(hash, id);
+        let mut members = Vec::from([hash.clone()]);
+        let mut next = 0;
+        while next < members.len() {
+            for cell in block(&members[next])? {
+                if let Some((key, _)) = cells.get_key_value(&cell) {
+                    if dense(key) && !cluster_of.contains_key(key.as_str()) {
+                        cluster_of.insert(key, id);
+                        members.push(cell);
+                    }
+                }
+            }
+            next += 1;
+        }
+        clusters.push(members);
+    }
+
+    // attach the sparse cells next to a dense cell
+    for hash in cells.keys().filter(|hash| !dense(hash)) {
+        let around = block(hash)?;
+        let id = around
+            .iter()
+            .filter(|cell| dense(cell))
+            .find_map(|cell| cluster_of.get(cell.as_str()).copied());
+        if let Some(id) = id {
+            clusters[id].push(hash.clone());
+            cluster_of.insert(hash, id);
+        }
+    }
+
+    let mut labels = vec![None; n];
+    let mut done = Vec::with_capacity(clusters.len());
+    for (id, mut members) in clusters.into_iter().enumerate() {
+        members.sort_unstable();
+        let mut count = 0;
+        let mut outline = Vec::new();
+        for hash in &members {
+            for &i in &cells[hash] {
+                labels[i] = Some(id);
+                count += 1;
+            }
+            let around = block(hash)?;
+            if around.len() < 9
+                || around
+                    .iter()
+                    .any(|cell| members.binary_search(cell).is_err())
+            {
+                outline.push(hash.clone());
+            }
+        }
+        done.push(Cluster {
+            cells: members,
+            outline,
+            count,
+        });
+    }
+    Ok(Clustering {
+        labels,
+        clusters: done,
+    })
+}
+
+/// The longest geohash length whose cells are at least `meters` across at every latitude up to
+/// `latitude`, if any.
+///
+/// With this length, all points within `meters` of a point are in its cell or the cells around
+/// it, so it can be used as the length of [`cluster_points`] for a DBSCAN `eps` of `meters`.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::eps_precision;
+///
+/// assert_eq!(eps_precision(100.0, 60.0), Some(6));
+/// assert_eq!(eps_precision(1000.0, 60.0), Some(5));
+/// assert_eq!(eps_precision(10_000_000.0, 60.0), None);
+/// ```
+pub fn eps_precision(meters: f64, latitude: f64) -> Option<usize> {
+    let c = Coord {
+        x: 0.0,
+        y: latitude.abs().min(90.0),
+    };
+    (1..=12).rev().find(|&len| cells_suffice(c, len, meters))
+}
@@ -0,0 +1,369 @@
+use crate::core::{encode_bits, grid_bits, grid_to_int, int_to_grid, BASE32, DECODER};
+use crate::{Coord, Direction, Format, GeohashError, Neighbors, Rect};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use libm::{floor, pow};
+
+/// A way of writing geohashes as strings: an alphabet of characters, and the way each
+/// character divides a cell into smaller cells.
+///
+/// Binary codecs, like the classic geohash, write the interleaved bits of an integer geohash
+/// a few bits per character, and decode to the same cells as [`decode_bbox_int`]. Grid codecs,
+/// like Geohash-36, divide every cell into a fixed grid of rows and columns, with the
+/// characters laid out row by row from the north-west corner of the grid.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{Codec, Coord};
+///
+/// let c = Coord { x: -120.6623, y: 35.3003 };
+/// assert_eq!(Codec::GEOHASH.encode(c, 5).unwrap(), geohash::encode(c, 5).unwrap());
+/// assert_eq!(Codec::BASE16.encode(c, 5).unwrap(), "4d8c0");
+///
+/// let cell = Codec::GEOHASH_36.decode_bbox("bdrdC26BqH").unwrap();
+/// assert!(cell.min().y < 51.504444 && 51.504444 < cell.max().y);
+/// assert!(cell.min().x < -0.086666 && -0.086666 < cell.max().x);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Codec {
+    alphabet: &'static str,
+    // the value of each byte, or 0xff if it's not in the alphabet, to avoid searching the
+    // alphabet when decoding
+    decoder: Option<&'static [u8; 256]>,
+    layout: Layout,
+}
+
+impl fmt::Debug for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Codec")
+            .field("alphabet", &self.alphabet)
+            .field("layout", &self.layout)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    // the number of bits of an integer geohash written by each character
+    Bits(u32),
+    Grid { rows: u32, cols: u32 },
+}
+
+// a cell on the grid of cells of some length, counted from the south-west corner
+struct Cell {
+    lat_idx: u64,
+    lon_idx: u64,
+    rows: u64,
+    cols: u64,
+}
+
+impl Codec {
+    /// The classic geohash, with the base32 alphabet of [`encode`](crate::encode).
+    pub const GEOHASH: Codec = Codec {
+        alphabet: BASE32,
+        decoder: Some(&DECODER),
+        layout: Layout::Bits(5),
+    };
+
+    /// Geohash-36, dividing every cell into a grid of 6 by 6 with a case-sensitive alphabet.
+    pub const GEOHASH_36: Codec = Codec {
+        alphabet: "23456789bBCdDFgGhHjJKlLMnNPqQrRtTVWX",
+        decoder: None,
+        layout: Layout::Grid { rows: 6, cols: 6 },
+    };
+
+    /// Integer geohashes written in base 4, two bits per character.
+    pub const BASE4: Codec = Codec {
+        alphabet: "0123",
+        decoder: None,
+        layout: Layout::Bits(2),
+    };
+
+    /// Integer geohashes written in hexadecimal, four bits per character.
+    pub const BASE16: Codec = Codec {
+        alphabet: "0123456789abcdef",
+        decoder: None,
+        layout: Layout::Bits(4),
+    };
+
+    /// Create a binary codec, whose alphabet of 2, 4, 8, 16, 32 or 64 distinct ASCII characters
+    /// sets the number of bits written by each character.
+    pub fn binary(alphabet: &'static str) -> Result<Codec, GeohashError> {
+        check_alphabet(alphabet)?;
+        let len = alphabet.len();
+        if !len.is_power_of_two() || !(2..=64).contains(&len) {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Alphabet,
+                reason: alphabet.into(),
+            });
+        }
+        Ok(Codec {
+            alphabet,
+            decoder: None,
+            layout: Layout::Bits(len.trailing_zeros()),
+        })
+    }
+
+    /// Create a grid codec, dividing every cell into `rows` by `cols` smaller cells, one for
+    /// each character of the alphabet.
+    pub fn grid(alphabet: &'static str, rows: u32, cols: u32) -> Result<Codec, GeohashError> {
+        check_alphabet(alphabet)?;
+        if rows as usize * cols as usize != alphabet.len() || alphabet.len() < 2 {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Alphabet,
+                reason: format!("{} for a grid of {} by {}", alphabet, rows, cols),
+            });
+        }
+        Ok(Codec {
+            alphabet,
+            decoder: None,
+            layout: Layout::Grid { rows, cols },
+        })
+    }
+
+    /// The alphabet of the codec, in order of value.
+    pub fn alphabet(&self) -> &'static str {
+        self.alphabet
+    }
+
+    /// The length of the longest strings the codec can encode.
+    pub fn max_len(&self) -> usize {
+        match self.layout {
+            Layout::Bits(bits) => (64 / bits) as usize,
+            // keep cells thousands of ulps wide, so that they round trip through their centers
+            Layout::Grid { rows, cols } => {
+                let side = rows.max(cols) as f64;
+                let mut len = 1;
+                while pow(side, (len + 1) as f64) <= (1u64 << 40) as f64 {
+                    len += 1;
+                }
+                len
+            }
+        }
+    }
+
+    /// Encode a coordinate to a string of `len` characters.
+    ///
+    /// Binary codecs encode like [`encode`](crate::encode) does, so a longitude of 180 or a
+    /// latitude of 90 wraps around to the first column or row of the grid.
+    pub fn encode(&self, c: Coord<f64>, len: usize) -> Result<String, GeohashError> {
+        if !(1..=self.max_len()).contains(&len) {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Codec,
+                reason: format!(
+                    "length {}, accepted values are between 1 and {}, inclusive",
+                    len,
+                    self.max_len()
+                ),
+            });
+        }
+        match self.layout {
+            Layout::Bits(bits) => {
+                let int_hash = encode_bits(c, bits * len as u32)?;
+                Ok(self.write_int(int_hash, bits, len))
+            }
+            Layout::Grid { rows, cols } => {
+                if !(-180.0..=180.0).contains(&c.x) || !(-90.0..=90.0).contains(&c.y) {
+                    return Err(GeohashError::InvalidCoordinateRange(c));
+                }
+                let (rows, cols) = (grid_size(rows, len), grid_size(cols, len));
+                let index = |v: f64, n: u64| (floor(v * n as f64) as u64).min(n - 1);
+                let cell = Cell {
+                    lat_idx: index((c.y + 90.0) / 180.0, rows),
+                    lon_idx: index((c.x + 180.0) / 360.0, cols),
+                    rows,
+                    cols,
+                };
+                Ok(self.write_cell(&cell, len))
+            }
+        }
+    }
+
+    /// Decode a string into the bounding box of its cell.
+    pub fn decode_bbox(&self, hash_str: &str) -> Result<Rect<f64>, GeohashError> {
+        let cell = self.read_cell(hash_str)?;
+        let (height, width) = (180.0 / cell.rows as f64, 360.0 / cell.cols as f64);
+        Ok(Rect::new(
+            Coord {
+                x: cell.lon_idx as f64 * width - 180.0,
+                y: cell.lat_idx as f64 * height - 90.0,
+            },
+            Coord {
+                x: (cell.lon_idx + 1) as f64 * width - 180.0,
+                y: (cell.lat_idx + 1) as f64 * height - 90.0,
+            },
+        ))
+    }
+
+    /// Decode a string into the coordinate at the center of its cell, with the longitude and
+    /// latitude errors, like [`decode`](crate::decode).
+    pub fn decode(&self, hash_str: &str) -> Result<(Coord<f64>, f64, f64), GeohashError> {
+        let rect = self.decode_bbox(hash_str)?;
+        Ok((rect.center(), rect.width() / 2.0, rect.height() / 2.0))
+    }
+
+    /// Find the neighboring cell in the given direction, wrapping around the world like
+    /// [`neighbor`](crate::neighbor).
+    pub fn neighbor(&self, hash_str: &str, direction: Direction) -> Result<String, GeohashError> {
+        let cell = self.read_cell(hash_str)?;
+        let (dlat, dlon) = direction.to_tuple();
+        let step = |idx: u64, d: f64, n: u64| ((idx + n) as i64 + d as i64) as u64 % n;
+        let neighbor = Cell {
+            lat_idx: step(cell.lat_idx, dlat, cell.rows),
+            lon_idx: step(cell.lon_idx, dlon, cell.cols),
+            ..cell
+        };
+        Ok(self.write_cell(&neighbor, hash_str.chars().count()))
+    }
+
+    /// Find all neighboring cells of a cell.
+    pub fn neighbors(&self, hash_str: &str) -> Result<Neighbors, GeohashError> {
+        Ok(Neighbors {
+            sw: self.neighbor(hash_str, Direction::SW)?,
+            s: self.neighbor(hash_str, Direction::S)?,
+            se: self.neighbor(hash_str, Direction::SE)?,
+            w: self.neighbor(hash_str, Direction::W)?,
+            e: self.neighbor(hash_str, Direction::E)?,
+            nw: self.neighbor(hash_str, Direction::NW)?,
+            n: self.neighbor(hash_str, Direction::N)?,
+            ne: self.neighbor(hash_str, Direction::NE)?,
+        })
+    }
+
+    pub(crate) fn symbol(&self, value: u64) -> char {
+        self.alphabet.as_bytes()[value as usize] as char
+    }
+
+    pub(crate) fn value(&self, c: char) -> Result<u64, GeohashError> {
+        let value = match self.decoder {
+            Some(decoder) => decoder
+                .get(c as usize)
+                .filter(|&&v| v != 0xff)
+                .map(|&v| v as u64),
+            None => self.alphabet.chars().position(|a| a == c).map(|v| v as u64),
+        };
+        value.ok_or(GeohashError::InvalidHashCharacter(c))
+    }
+
+    // writes the lowest `bits * len` bits of an integer geohash, `bits` bits per character
+    pub(crate) fn write_int(&self, int_hash: u64, bits: u32, len: usize) -> String {
+        (0..len as u32)
+            .rev()
+            .map(|i| self.symbol(int_hash >> (bits * i) & ((1 << bits) - 1)))
+            .collect()
+    }
+
+    fn write_cell(&self, cell: &Cell, len: usize) -> String {
+        match self.layout {
+            Layout::Bits(bits) => {
+                let int_hash =
+                    grid_to_int(cell.lat_idx as u32, cell.lon_idx as u32, bits * len as u32);
+                self.write_int(int_hash, bits, len)
+            }
+            Layout::Grid { rows, cols } => {
+                let (rows, cols) = (rows as u64, cols as u64);
+                let (mut lat_idx, mut lon_idx) = (cell.lat_idx, cell.lon_idx);
+                let mut chars = Vec::with_capacity(len);
+                for _ in 0..len {
+                    // rows are counted from the north within each cell
+                    let row = rows - 1 - lat_idx % rows;
+                    chars.push(self.symbol(row * cols + lon_idx % cols));
+                    lat_idx /= rows;
+                    lon_idx /= cols;
+                }
+                chars.iter().rev().collect()
+            }
+        }
+    }
+
+    // checks the length of a string, returning its number of characters
+    fn check_len(&self, hash_str: &str) -> Result<usize, GeohashError> {
+        let len = hash_str.chars().count();
+        if len == 0 {
+            return Err(GeohashError::InvalidHash(
+                "Hash string must not be empty".into(),
+            ));
+        }
+        if len > self.max_len() {
+            return Err(GeohashError::InvalidHash(format!(
+                "Length of hash string greater than maximum allowed length of {}",
+                self.max_len()
+            )));
+        }
+        Ok(len)
+    }
+
+    // reads a string of a binary codec into its integer geohash, `bits` bits per character,
+    // with the decoded bits in the lowest bits of the result
+    pub(crate) fn read_int(&self, hash_str: &str) -> Result<u64, GeohashError> {
+        self.check_len(hash_str)?;
+        let bits = match self.layout {
+            Layout::Bits(bits) => bits,
+            Layout::Grid { .. } => unreachable!("only binary codecs have integer geohashes"),
+        };
+        let mut int_hash = 0;
+        for c in hash_str.chars() {
+            int_hash = int_hash << bits | self.value(c)?;
+        }
+        Ok(int_hash)
+    }
+
+    fn read_cell(&self, hash_str: &str) -> Result<Cell, GeohashError> {
+        let len = self.check_len(hash_str)?;
+        match self.layout {
+            Layout::Bits(bits) => {
+                let int_hash = self.read_int(hash_str)?;
+                let total = bits * len as u32;
+                let (lat_bits, lon_bits) = grid_bits(total);
+                let (lat_idx, lon_idx) = int_to_grid(int_hash, total);
+                Ok(Cell {
+                    lat_idx: lat_idx as u64,
+                    lon_idx: lon_idx as u64,
+                    rows: 1 << lat_bits,
+                    cols: 1 << lon_bits,
+                })
+            }
+            Layout::Grid { rows, cols } => {
+                let (rows, cols) = (rows as u64, cols as u64);
+                let mut cell = Cell {
+                    lat_idx: 0,
+                    lon_idx: 0,
+                    rows: 1,
+                    cols: 1,
+                };
+                for c in hash_str.chars() {
+                    let value = self.value(c)?;
+                    cell.lat_idx = cell.lat_idx * rows + (rows - 1 - value / cols);
+                    cell.lon_idx = cell.lon_idx * cols + value % cols;
+                    cell.rows *= rows;
+                    cell.cols *= cols;
+                }
+                Ok(cell)
+            }
+        }
+    }
+}
+
+// the number of rows or columns of the grid of cells of length `len`
+fn grid_size(divisions: u32, len: usize) -> u64 {
+    (divisions as u64).pow(len as u32)
+}
+
+// alphabets must be made of distinct ASCII characters
+fn check_alphabet(alphabet: &str) -> Result<(), GeohashError> {
+    let bytes = alphabet.as_bytes();
+    let distinct = bytes
+        .iter()
+        .enumerate()
+        .all(|(i, b)| !bytes[..i].contains(b));
+    if !alphabet.is_ascii() || !distinct {
+        return Err(GeohashError::InvalidFormat {
+            format: Format::Alphabet,
+            reason: alphabet.into(),
+        });
+    }
+    Ok(())
+}
@@ -1,23 +1,19 @@
 use crate::neighbors::Direction;
-use crate::{Coord, GeohashError, Neighbors, Rect};
-use alloc::string::{String, ToString};
-use core::ops::Range;
+use crate::{Codec, Coord, GeohashError, Neighbors, Rect};
+use alloc::string::String;
+use core::ops::RangeInclusive;
 use libm::ldexp;
 
-const LEN_RANGE: Range<usize> = 1..13;
+// the length of the longest geohashes
+const MAX_LEN: usize = 12;
+const BITS_RANGE: RangeInclusive<u32> = 1..=64;
 
 // the alphabet for the base32 encoding used in geohashing
-#[rustfmt::skip]
-const BASE32_CODES: [char; 32] = [
-    '0', '1', '2', '3', '4', '5', '6', '7',
-    '8', '9', 'b', 'c', 'd', 'e', 'f', 'g',
-    'h', 'j', 'k', 'm', 'n', 'p', 'q', 'r',
-    's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
-];
+pub(crate) const BASE32: &str = "0123456789bcdefghjkmnpqrstuvwxyz";
 
 // array that is indexed into to get the value of a character in our base32 alphabet
 #[rustfmt::skip]
-const DECODER: [u8; 256] = [
+pub(crate) const DECODER: [u8; 256] = [
     0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
     0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
     0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
@@ -92,6 +88,33 @@ fn squash(x: u64) -> u32 {
     new_x as u32
 }
 
+// spread3 takes the lowest 21 bits of a u32 and deposits them into every third bit of a u64,
+// starting from the lowest one
+#[inline]
+pub(crate) fn spread3(x: u32) -> u64 {
+    let mut new_x = x as u64 & 0x1fffff;
+    new_x = (new_x | (new_x << 32)) & 0x001f00000000ffff;
+    new_x = (new_x | (new_x << 16)) & 0x001f0000ff0000ff;
+    new_x = (new_x | (new_x << 8)) & 0x100f00f00f00f00f;
+    new_x = (new_x | (new_x << 4)) & 0x10c30c30c30c30c3;
+    new_x = (new_x | (new_x << 2)) & 0x1249249249249249;
+
+    new_x
+}
+
+// squashes every third bit of a u64, starting from the lowest one, into a u32
+#[inline]
+pub(crate) fn squash3(x: u64) -> u32 {
+    let mut new_x = x & 0x1249249249249249;
+    new_x = (new_x | (new_x >> 2)) & 0x10c30c30c30c30c3;
+    new_x = (new_x | (new_x >> 4)) & 0x100f00f00f00f00f;
+    new_x = (new_x | (new_x >> 8)) & 0x001f0000ff0000ff;
+    new_x = (new_x | (new_x >> 16)) & 0x001f00000000ffff;
+    new_x = (new_x | (new_x >> 32)) & 0x00000000001fffff;
+
+    new_x as u32
+}
+
 // uses the squash function to create a 32 from the even bits
 // then shifts the input right and squashes to create a u32 from the odd bits
 #[inline]
@@ -99,6 +122,19 @@ fn deinterleave(x: u64) -> (u32, u32) {
     (squash(x), squash(x >> 1))
 }
 
+// interleaves the latitude and longitude of a coordinate into a left-aligned 64 bit hash,
+// the coordinate must already have been checked to be in range
+#[inline]
+fn interleave_coord(c: Coord<f64>) -> u64 {
+    // divides the latitude by 180, then adds 1.5 to give a value between 1 and 2
+    // then we take the first 32 bits of the significand as a u32
+    let lat32 = ((c.y * 0.005555555555555556 + 1.5).to_bits() >> 20) as u32;
+    // same as latitude, but a division by 360 instead of 180
+    let lon32 = ((c.x * 0.002777777777777778 + 1.5).to_bits() >> 20) as u32;
+
+    interleave(lat32, lon32)
+}
+
 /// Encode a coordinate to a geohash with length `len`.
 ///
 /// ### Examples
@@ -123,11 +159,10 @@ fn deinterleave(x: u64) -> (u32, u32) {
 /// assert_eq!(geohash_string, "9q60y60rhs");
 /// ```
 pub fn encode(c: Coord<f64>, len: usize) -> Result<String, GeohashError> {
-    if !LEN_RANGE.contains(&len) {
+    if !(1..=MAX_LEN).contains(&len) {
         return Err(GeohashError::InvalidLength(len));
     }
-
-    Ok(encode_iter(c)?.take(len).collect())
+    Codec::GEOHASH.encode(c, len)
 }
 
 /// Encode a coordinate to a geohash.
@@ -156,33 +191,12 @@ pub fn encode(c: Coord<f64>, len: usize) -> Result<String, GeohashError> {
 /// assert_eq!(geohash_iter.collect::<Vec<_>>(), ['9', 'q', '6', '0', 'y', '6', '0', 'r', 'h', 's', 'g', 'g']);
 /// ```
 pub fn encode_iter(c: Coord<f64>) -> Result<impl Iterator<Item = char>, GeohashError> {
-    let max_lat = 90f64;
-    let min_lat = -90f64;
-    let max_lon = 180f64;
-    let min_lon = -180f64;
-
-    if !(min_lon..=max_lon).contains(&c.x) || !(min_lat..=max_lat).contains(&c.y) {
-        return Err(GeohashError::InvalidCoordinateRange(c));
-    }
+    let int_hash = encode_bits(c, MAX_LEN as u32 * 5)?;
 
-    // divides the latitude by 180, then adds 1.5 to give a value between 1 and 2
-    // then we take the first 32 bits of the significand as a u32
-    let lat32 = ((c.y * 0.005555555555555556 + 1.5).to_bits() >> 20) as u32;
-    // same as latitude, but a division by 360 instead of 180
-    let lon32 = ((c.x * 0.002777777777777778 + 1.5).to_bits() >> 20) as u32;
-
-    let mut interleaved_int = interleave(lat32, lon32);
-
-    // loop through and take the first 5 bits of the interleaved value ech iteration
-    Ok(LEN_RANGE.map(move |_| {
-        // shifts so that the high 5 bits are now the low five bits, then masks to get their value
-        let code = (interleaved_int >> 59) as usize & (0x1f);
-        // uses that value to index into the array of base32 codes
-        let out = BASE32_CODES[code];
-        // shifts the interleaved bits left by 5, so we get the next 5 bits on the next iteration
-        interleaved_int <<= 5;
-        out
-    }))
+    // takes 5 bits at a time from the highest ones, and converts them to their base32 codes
+    Ok((0..MAX_LEN)
+        .rev()
+        .map(move |i| value_char((int_hash >> (5 * i)) as u8)))
 }
 
 /// Decode geohash string into latitude, longitude
@@ -197,34 +211,114 @@ pub fn encode_iter(c: Coord<f64>) -> Result<impl Iterator<Item = char>, GeohashE
 /// * min_lon
 /// * max_lon
 pub fn decode_bbox(hash_str: &str) -> Result<Rect<f64>, GeohashError> {
-    let bits = hash_str.len() * 5;
+    Codec::GEOHASH.decode_bbox(hash_str)
+}
+
+// converts a geohash string into its integer value, with the decoded bits in the lowest
+// `5 * hash_str.len()` bits of the result
+pub(crate) fn hash_to_int(hash_str: &str) -> Result<u64, GeohashError> {
+    Codec::GEOHASH.read_int(hash_str)
+}
+
+// the base32 value of a single geohash character
+pub(crate) fn char_value(c: char) -> Result<u8, GeohashError> {
+    Codec::GEOHASH.value(c).map(|v| v as u8)
+}
+
+// the geohash character for a base32 value
+pub(crate) fn value_char(v: u8) -> char {
+    Codec::GEOHASH.symbol(v as u64 & 0x1f)
+}
 
-    if hash_str.is_empty() {
-        return Err(GeohashError::InvalidHash(
-            "Hash string must not be empty".to_string(),
-        ));
+// converts the lowest `5 * len` bits of an integer hash back into a geohash string
+pub(crate) fn int_to_hash(int_hash: u64, len: usize) -> String {
+    Codec::GEOHASH.write_int(int_hash, 5, len)
+}
+
+/// Encode a coordinate to an integer geohash of `bits` bits.
+///
+/// The hash is stored in the lowest `bits` bits of the result, with the same bit layout as the
+/// base32 geohash (a length `n` geohash is a `5 * n` bit integer hash). Integer hashes sort in
+/// Z-order, which makes them suitable keys for sorted stores.
+///
+/// ### Examples
+///
+/// ```rust
+/// let coord = geohash::Coord { x: -120.6623, y: 35.3003 };
+///
+/// assert_eq!(geohash::encode_int(coord, 25).unwrap(), 0b01001_10110_00110_00000_11110);
+/// assert_eq!(geohash::encode_int(coord, 3).unwrap(), 0b010);
+/// ```
+pub fn encode_int(c: Coord<f64>, bits: u32) -> Result<u64, GeohashError> {
+    if !BITS_RANGE.contains(&bits) {
+        return Err(GeohashError::InvalidBits(bits));
     }
+    if !(-180.0..=180.0).contains(&c.x) || !(-90.0..=90.0).contains(&c.y) {
+        return Err(GeohashError::InvalidCoordinateRange(c));
+    }
+    // the upper bounds of the ranges would overflow into the exponent, keep them in the last
+    // row and column of the grid instead
+    let c = Coord {
+        x: c.x.min(180.0 - 1e-9),
+        y: c.y.min(90.0 - 1e-9),
+    };
+    encode_bits(c, bits)
+}
 
-    if hash_str.len() > 12 {
-        return Err(GeohashError::InvalidHash(
-            "Length of hash string greater than maximum allowed length".to_string(),
-        ));
+// the `bits` bit hash written by `encode`, which unlike `encode_int` doesn't clamp the upper
+// bounds of the ranges: they overflow into the first row and column, as they always have
+pub(crate) fn encode_bits(c: Coord<f64>, bits: u32) -> Result<u64, GeohashError> {
+    if !(-180.0..=180.0).contains(&c.x) || !(-90.0..=90.0).contains(&c.y) {
+        return Err(GeohashError::InvalidCoordinateRange(c));
     }
+    Ok(interleave_coord(c) >> (64 - bits))
+}
 
-    let mut int_hash: u64 = 0;
-    for c in hash_str.bytes() {
-        // getting the value from the array converts from the base32 alphabet to an integer value
-        let hash_value = DECODER[c as usize];
-        // this means that we have indexed into the position of an invalid character
-        if hash_value == 0xff {
-            return Err(GeohashError::InvalidHashCharacter(c as char));
-        }
-        // shift int_hash and deposit the newly decoded bits into its lowest bits
-        int_hash <<= 5;
-        int_hash |= hash_value as u64;
+/// Decode an integer geohash of `bits` bits, as produced by [`encode_int`], into its bounding
+/// box.
+///
+/// ### Examples
+///
+/// ```rust
+/// let rect = geohash::decode_bbox_int(0b01001_10110_00110_00000_11110, 25).unwrap();
+///
+/// assert_eq!(rect, geohash::decode_bbox("9q60y").unwrap());
+/// ```
+pub fn decode_bbox_int(int_hash: u64, bits: u32) -> Result<Rect<f64>, GeohashError> {
+    if !BITS_RANGE.contains(&bits) {
+        return Err(GeohashError::InvalidBits(bits));
     }
+    Ok(bbox_int_with_precision(
+        int_hash & (u64::MAX >> (64 - bits)),
+        bits,
+    ))
+}
+
+// the number of latitude and longitude bits in a hash of `bits` bits, longitude takes
+// the extra bit when `bits` is odd since geohashes start with a longitude bit
+#[inline]
+pub(crate) fn grid_bits(bits: u32) -> (u32, u32) {
+    let lat_bits = bits / 2;
+    (lat_bits, bits - lat_bits)
+}
 
-    Ok(bbox_int_with_precision(int_hash, bits as u32))
+// splits an integer hash of `bits` bits into its row (latitude) and column (longitude)
+// indices on the grid of cells of that precision, counted from the south-west corner
+pub(crate) fn int_to_grid(int_hash: u64, bits: u32) -> (u32, u32) {
+    let (lat_bits, lon_bits) = grid_bits(bits);
+    let (lat32, lon32) = deinterleave(int_hash << (64 - bits));
+    (
+        (lat32 as u64 >> (32 - lat_bits)) as u32,
+        (lon32 as u64 >> (32 - lon_bits)) as u32,
+    )
+}
+
+// the inverse of `int_to_grid`
+pub(crate) fn grid_to_int(lat_idx: u32, lon_idx: u32, bits: u32) -> u64 {
+    let (lat_bits, lon_bits) = grid_bits(bits);
+    let lat32 = ((lat_idx as u64) << (32 - lat_bits)) as u32;
+    let lon32 = ((lon_idx as u64) << (32 - lon_bits)) as u32;
+    interleave(lat32, lon32) >> (64 - bits)
 }
 
 fn decode_range(x: u32, r: f64) -> f64 {
@@ -234,9 +328,8 @@ fn decode_range(x: u32, r: f64) -> f64 {
     2.0 * r * (p - 1.0) - r
 }
 
-fn error_with_precision(bits: u32) -> (f64, f64) {
-    let lat_bits = bits / 2;
-    let long_bits = bits - lat_bits;
+pub(crate) fn error_with_precision(bits: u32) -> (f64, f64) {
+    let (lat_bits, long_bits) = grid_bits(bits);
 
     // the ldexp(x, n) function is equivalent to x * 2^n but with better performance
     let lat_err = ldexp(180.0, -(lat_bits as i32));
@@ -244,7 +337,7 @@ fn error_with_precision(bits: u32) -> (f64, f64) {
     (lat_err, long_err)
 }
 
-fn bbox_int_with_precision(hash: u64, bits: u32) -> Rect<f64> {
+pub(crate) fn bbox_int_with_precision(hash: u64, bits: u32) -> Rect<f64> {
     let full_hash = hash << (64 - bits);
     let (lat_int, long_int) = deinterleave(full_hash);
     let lat = decode_range(lat_int, 90.0);
@@ -260,18 +353,6 @@ fn bbox_int_with_precision(hash: u64, bits: u32) -> Rect<f64> {
     )
 }
 
-#[cfg(not(feature = "std"))]
-// `f64::rem_euclid` is unavailable in no-std builds, so keep a small
-// equivalent helper for wrapping coordinates.
-fn rem_euclid(x: f64, rhs: f64) -> f64 {
-    let r = x % rhs;
-    if r < 0.0 {
-        r + rhs.abs()
-    } else {
-        r
-    }
-}
-
 /// Decode a geohash into a coordinate with some longitude/latitude error. The
 /// return value is `(<coordinate>, <longitude error>, <latitude error>)`.
 ///
@@ -345,19 +426,7 @@ pub fn decode(hash_str: &str) -> Result<(Coord<f64>, f64, f64), GeohashError> {
 /// # }
 /// ```
 pub fn neighbor(hash_str: &str, direction: Direction) -> Result<String, GeohashError> {
-    let (coord, lon_err, lat_err) = decode(hash_str)?;
-    let (dlat, dlng) = direction.to_tuple();
-    #[cfg(not(feature = "std"))]
-    let neighbor_coord = Coord {
-        x: rem_euclid((coord.x + 2f64 * lon_err.abs() * dlng) + 180.0, 360.0) - 180.0,
-        y: rem_euclid((coord.y + 2f64 * lat_err.abs() * dlat) + 90.0, 180.0) - 90.0,
-    };
-    #[cfg(feature = "std")]
-    let neighbor_coord = Coord {
-        x: ((coord.x + 2f64 * lon_err.abs() * dlng) + 180.0).rem_euclid(360.0) - 180.0,
-        y: ((coord.y + 2f64 * lat_err.abs() * dlat) + 90.0).rem_euclid(180.0) - 90.0,
-    };
-    encode(neighbor_coord, hash_str.len())
+    Codec::GEOHASH.neighbor(hash_str, direction)
 }
 
 /// Find all neighboring geohashes for the given geohash.
@@ -384,14 +453,5 @@ pub fn neighbor(hash_str: &str, direction: Direction) -> Result<String, GeohashE
 /// );
 /// ```
 pub fn neighbors(hash_str: &str) -> Result<Neighbors, GeohashError> {
-    Ok(Neighbors {
-        sw: neighbor(hash_str, Direction::SW)?,
-        s: neighbor(hash_str, Direction::S)?,
-        se: neighbor(hash_str, Direction::SE)?,
-        w: neighbor(hash_str, Direction::W)?,
-        e: neighbor(hash_str, Direction::E)?,
-        nw: neighbor(hash_str, Direction::NW)?,
-        n: neighbor(hash_str, Direction::N)?,
-        ne: neighbor(hash_str, Direction::NE)?,
-    })
+    Codec::GEOHASH.neighbors(hash_str)
 }
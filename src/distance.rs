@@ -0,0 +1,35 @@
+use crate::{Coord, Rect};
+use libm::{asin, cos, sin, sqrt};
+
+/// Mean radius of the earth in meters, as used for all distance calculations.
+pub(crate) const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Length in meters of one degree of latitude.
+pub(crate) const METERS_PER_DEGREE: f64 = EARTH_RADIUS * core::f64::consts::PI / 180.0;
+
+/// Great-circle distance in meters between two coordinates, using the haversine formula.
+pub(crate) fn haversine(a: Coord<f64>, b: Coord<f64>) -> f64 {
+    let (lat_a, lat_b) = (a.y.to_radians(), b.y.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lon = (b.x - a.x).to_radians();
+
+    let h = sin(d_lat / 2.0) * sin(d_lat / 2.0)
+        + cos(lat_a) * cos(lat_b) * sin(d_lon / 2.0) * sin(d_lon / 2.0);
+    2.0 * EARTH_RADIUS * asin(sqrt(h).min(1.0))
+}
+
+/// Lower bound in meters on the distance from `c` to any point whose longitude differs from
+/// `c.x` by at least `d_lon` degrees.
+pub(crate) fn meridian_distance(c: Coord<f64>, d_lon: f64) -> f64 {
+    // past 90 degrees the closest such point is towards the nearest pole
+    EARTH_RADIUS * asin(sin(d_lon.min(90.0).to_radians()) * cos(c.y.to_radians()))
+}
+
+/// Area in square meters of a longitude/latitude rectangle on the sphere.
+pub(crate) fn rect_area(rect: Rect<f64>) -> f64 {
+    let (min, max) = (rect.min(), rect.max());
+    EARTH_RADIUS
+        * EARTH_RADIUS
+        * (max.x - min.x).to_radians()
+        * (sin(max.y.to_radians()) - sin(min.y.to_radians()))
+}
@@ -0,0 +1,200 @@
+use crate::neighbors::block;
+use crate::{encode, Coord, Format, GeohashError};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A stop of an object in a geohash cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DwellEvent {
+    /// The cell the object stopped in.
+    pub geohash: String,
+    /// The timestamp of the first sample of the stop.
+    pub enter: u64,
+    /// The timestamp of the last sample of the stop.
+    pub exit: u64,
+    /// The number of samples of the stop, including the samples briefly outside of its cell.
+    pub count: usize,
+}
+
+/// A streaming detector of the places where an object stops, from samples of its position.
+///
+/// Samples are grouped into visits: a visit starts in the cell of its first sample, and lasts
+/// as long as the following samples stay in that cell, or also in the cells around it with
+/// [`with_neighbors`](DwellDetector::with_neighbors). A visit lasting at least the minimum
+/// duration is reported as a [`DwellEvent`] once it's over.
+///
+/// GPS jitter near the border of a cell would split a stop into many short visits, so with
+/// [`with_hysteresis`](DwellDetector::with_hysteresis) a visit only ends after that many
+/// samples in a row outside of it. Samples outside of a visit followed by a sample back inside
+/// are counted as part of it.
+///
+/// A detector follows a single object, with samples in the order of their timestamps.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{Coord, DwellDetector};
+///
+/// let mut detector = DwellDetector::new(7, 300).unwrap().with_hysteresis(2);
+/// let depot = Coord { x: 2.2945, y: 48.8582 };
+/// let road = Coord { x: 2.3100, y: 48.8600 };
+///
+/// let mut events = Vec::new();
+/// for t in (0..600).step_by(60) {
+///     events.extend(detector.push(t, depot).unwrap());
+/// }
+/// for t in (600..900).step_by(60) {
+///     events.extend(detector.push(t, road).unwrap());
+/// }
+/// events.extend(detector.finish());
+///
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].geohash, "u09tunq");
+/// assert_eq!((events[0].enter, events[0].exit, events[0].count), (0, 540, 10));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DwellDetector {
+    len: usize,
+    min_duration: u64,
+    neighbors: bool,
+    hysteresis: usize,
+    visit: Option<Visit>,
+    last_timestamp: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct Visit {
+    geohash: String,
+    // the cells counted as inside of the visit
+    area: Vec<String>,
+    enter: u64,
+    exit: u64,
+    count: usize,
+    // the samples in a row outside of the visit
+    outside: Vec<(u64, String)>,
+}
+
+impl DwellDetector {
+    /// Create a detector of stops of at least `min_duration` seconds in geohashes of length
+    /// `len`.
+    pub fn new(len: usize, min_duration: u64) -> Result<Self, GeohashError> {
+        if !(1..=12).contains(&len) {
+            return Err(GeohashError::InvalidLength(len));
+        }
+        Ok(DwellDetector {
+            len,
+            min_duration,
+            neighbors: false,
+            hysteresis: 1,
+            visit: None,
+            last_timestamp: None,
+        })
+    }
+
+    /// Whether the 8 cells around the cell of a visit count as inside of it. These wrap around
+    /// the antimeridian but not around the poles.
+    pub fn with_neighbors(mut self, neighbors: bool) -> Self {
+        self.neighbors = neighbors;
+        self
+    }
+
+    /// The number of samples in a row outside of a visit that end it, at least one.
+    pub fn with_hysteresis(mut self, samples: usize) -> Self {
+        self.hysteresis = samples.max(1);
+        self
+    }
+
+    /// Add the next sample, returning the stop it ended, if any.
+    pub fn push(
+        &mut self,
+        timestamp: u64,
+        c: Coord<f64>,
+    ) -> Result<Option<DwellEvent>, GeohashError> {
+        if let Some(last) = self.last_timestamp {
+            if timestamp < last {
+                return Err(GeohashError::InvalidFormat {
+                    format: Format::Time,
+                    reason: format!("sample at {} after a sample at {}", timestamp, last),
+                });
+            }
+        }
+        let geohash = encode(c, self.len)?;
+        self.last_timestamp = Some(timestamp);
+
+        let visit = match self.visit.as_mut() {
+            Some(visit) => visit,
+            None => {
+                self.visit = Some(self.start(timestamp, geohash)?);
+                return Ok(None);
+            }
+        };
+        visit.add(timestamp, geohash);
+        if visit.outside.len() < self.hysteresis {
+            return Ok(None);
+        }
+
+        // the object left, the samples outside start the next visit
+        let ended = self.visit.take().expect("a visit is in progress");
+        let mut outside = ended.outside.iter().cloned();
+        let (timestamp, geohash) = outside.next().expect("a sample is outside");
+        let mut next = self.start(timestamp, geohash)?;
+        // fewer samples than the hysteresis, so the next visit goes on
+        for (timestamp, geohash) in outside {
+            next.add(timestamp, geohash);
+        }
+        self.visit = Some(next);
+        Ok(self.event(ended))
+    }
+
+    /// End the visit in progress, returning it if it was a stop.
+    ///
+    /// The samples outside of the visit at the end, too few to tell a new stop from jitter, are
+    /// left out.
+    pub fn finish(&mut self) -> Option<DwellEvent> {
+        self.last_timestamp = None;
+        let visit = self.visit.take()?;
+        self.event(visit)
+    }
+
+    fn start(&self, timestamp: u64, geohash: String) -> Result<Visit, GeohashError> {
+        let area = if self.neighbors {
+            block(&geohash)?
+        } else {
+            Vec::from([geohash.clone()])
+        };
+        Ok(Visit {
+            geohash,
+            area,
+            enter: timestamp,
+            exit: timestamp,
+            count: 1,
+            outside: Vec::new(),
+        })
+    }
+
+    fn event(&self, visit: Visit) -> Option<DwellEvent> {
+        if visit.exit - visit.enter < self.min_duration {
+            return None;
+        }
+        Some(DwellEvent {
+            geohash: visit.geohash,
+            enter: visit.enter,
+            exit: visit.exit,
+            count: visit.count,
+        })
+    }
+}
+
+impl Visit {
+    fn add(&mut self, timestamp: u64, geohash: String) {
+        if self.area.contains(&geohash) {
+            // the object came back, the samples outside were jitter
+            self.count += self.outside.len() + 1;
+            self.outside.clear();
+            self.exit = timestamp;
+        } else {
+            self.outside.push((timestamp, geohash));
+        }
+    }
+}
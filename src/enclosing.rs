@@ -0,0 +1,120 @@
+use crate::core::{error_with_precision, grid_bits, grid_to_int, int_to_hash};
+use crate::geometry::{bounding_rect, in_range};
+use crate::ranges::cell_span;
+use crate::{GeohashError, Rect};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+use geo_types::Geometry;
+
+// the number of bits of the longest geohashes, and of their rows and columns
+const BITS: u32 = 60;
+const GRID_BITS: u32 = 30;
+
+// an inclusive range of rows or of columns
+type Span = RangeInclusive<u32>;
+
+/// Find the longest geohash whose cell contains a geometry, such as a [`Rect`].
+///
+/// This is the common prefix of the geohashes of the south-west and north-east corners of the
+/// bounding box of the geometry, where a corner on the north or east edge of a cell is taken
+/// in that cell, so a rectangle is contained by the cells [`cover_rect`](crate::cover_rect)
+/// would return for it. There is no such geohash if the corners are in different cells of
+/// length 1. The bounding box doesn't wrap around the antimeridian.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{decode_bbox, enclosing_hash, Coord, Rect};
+///
+/// let rect = Rect::new(Coord { x: 2.29, y: 48.85 }, Coord { x: 2.30, y: 48.86 });
+/// assert_eq!(enclosing_hash(rect).unwrap().as_deref(), Some("u09tu"));
+///
+/// // a cell is enclosed by itself
+/// assert_eq!(enclosing_hash(decode_bbox("u09").unwrap()).unwrap().as_deref(), Some("u09"));
+///
+/// // london spans the prime meridian, between cells of length 1
+/// let london = Rect::new(Coord { x: -0.2, y: 51.4 }, Coord { x: 0.1, y: 51.6 });
+/// assert_eq!(enclosing_hash(london).unwrap(), None);
+/// ```
+pub fn enclosing_hash<G>(geometry: G) -> Result<Option<String>, GeohashError>
+where
+    G: Into<Geometry<f64>>,
+{
+    let (rows, cols) = match corner_cells(geometry.into())? {
+        Some(corners) => corners,
+        None => return Ok(None),
+    };
+    let lo = grid_to_int(*rows.start(), *cols.start(), BITS);
+    let hi = grid_to_int(*rows.end(), *cols.end(), BITS);
+    // the hashes are in the lowest 60 bits
+    let len = ((lo ^ hi).leading_zeros() - (64 - BITS)) as usize / 5;
+    Ok((len > 0).then(|| int_to_hash(lo >> (BITS - len as u32 * 5), len)))
+}
+
+/// Find the longest geohashes of the same length whose cells contain a geometry, such as a
+/// [`Rect`], with at most `max` of them.
+///
+/// This gives a tighter cover than [`enclosing_hash`], and works when a geometry spans the
+/// edge between cells of length 1. With a `max` of 4 the geometry can span the corner between
+/// cells, and with 9 it can be up to the size of a cell anywhere. The geohashes are the cells
+/// [`cover_rect`](crate::cover_rect) would return for the bounding box of the geometry, in
+/// Z-order, and are empty if even the cells of length 1 would be more than `max`.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{enclosing_cells, Coord, Rect};
+///
+/// let london = Rect::new(Coord { x: -0.2, y: 51.4 }, Coord { x: 0.1, y: 51.6 });
+/// assert_eq!(enclosing_cells(london, 4).unwrap(), ["gcpu", "gcpv", "u10h", "u10j"]);
+/// assert_eq!(enclosing_cells(london, 2).unwrap(), ["gcp", "u10"]);
+/// ```
+pub fn enclosing_cells<G>(geometry: G, max: usize) -> Result<Vec<String>, GeohashError>
+where
+    G: Into<Geometry<f64>>,
+{
+    let (rows, cols) = match corner_cells(geometry.into())? {
+        Some(corners) => corners,
+        None => return Ok(Vec::new()),
+    };
+    for len in (1..=12).rev() {
+        let bits = len as u32 * 5;
+        let (lat_bits, lon_bits) = grid_bits(bits);
+        let coarse = |span: &Span, bits: u32| {
+            (span.start() >> (GRID_BITS - bits))..=(span.end() >> (GRID_BITS - bits))
+        };
+        let (len_rows, len_cols) = (coarse(&rows, lat_bits), coarse(&cols, lon_bits));
+        let count = len_rows.clone().count() * len_cols.clone().count();
+        if count <= max {
+            let mut cells: Vec<u64> = len_rows
+                .flat_map(|row| len_cols.clone().map(move |col| grid_to_int(row, col, bits)))
+                .collect();
+            cells.sort_unstable();
+            return Ok(cells
+                .into_iter()
+                .map(|cell| int_to_hash(cell, len))
+                .collect());
+        }
+    }
+    Ok(Vec::new())
+}
+
+// the rows and columns of the longest geohashes overlapping the bounding box of the geometry
+fn corner_cells(geometry: Geometry<f64>) -> Result<Option<(Span, Span)>, GeohashError> {
+    let rect: Rect<f64> = match bounding_rect(&geometry) {
+        Some(rect) => rect,
+        None => return Ok(None),
+    };
+    let (min, max) = (rect.min(), rect.max());
+    for c in [min, max] {
+        if !in_range(c) {
+            return Err(GeohashError::InvalidCoordinateRange(c));
+        }
+    }
+    let (height, width) = error_with_precision(BITS);
+    Ok(Some((
+        cell_span(min.y + 90.0, max.y + 90.0, height, 180.0),
+        cell_span(min.x + 180.0, max.x + 180.0, width, 360.0),
+    )))
+}
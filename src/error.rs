@@ -4,11 +4,17 @@ use core::{error::Error, fmt};
 use crate::Coord;
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum GeohashError {
     InvalidHashCharacter(char),
     InvalidCoordinateRange(Coord<f64>),
     InvalidLength(usize),
+    InvalidBits(u32),
     InvalidHash(String),
+    InvalidFormat { format: Format, reason: String },
+    InvalidWeight(f64),
+    PrecisionMismatch { expected: usize, found: usize },
+    TooManyCells { count: u64, max: usize },
 }
 
 impl fmt::Display for GeohashError {
@@ -23,9 +29,65 @@ impl fmt::Display for GeohashError {
                 "Invalid length specified: {}. Accepted values are between 1 and 12, inclusive",
                 len
             ),
+            GeohashError::InvalidBits(bits) => write!(
+                f,
+                "Invalid number of bits specified: {}. Accepted values are between 1 and 64, inclusive",
+                bits
+            ),
             GeohashError::InvalidHash(msg) => write!(f, "Invalid input hash: {}", msg),
+            GeohashError::InvalidFormat { format, reason } => {
+                write!(f, "Invalid {}: {}", format, reason)
+            }
+            GeohashError::InvalidWeight(weight) => write!(f, "Invalid weight: {}", weight),
+            GeohashError::PrecisionMismatch { expected, found } => write!(
+                f,
+                "Precision mismatch: expected {}, found {}",
+                expected, found
+            ),
+            GeohashError::TooManyCells { count, max } => write!(
+                f,
+                "Too many cells: {} are needed, at most {} are allowed",
+                count, max
+            ),
         }
     }
 }
 
 impl Error for GeohashError {}
+
+/// The kind of input rejected with [`GeohashError::InvalidFormat`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    SortKey,
+    Tile,
+    PlusCode,
+    Locator,
+    GridReference,
+    S2Cell,
+    Alphabet,
+    Altitude,
+    Time,
+    Track,
+    Codec,
+    SpaceTimeKey,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::SortKey => "sort key",
+            Format::Tile => "tile",
+            Format::PlusCode => "plus code",
+            Format::Locator => "Maidenhead locator",
+            Format::GridReference => "grid reference",
+            Format::S2Cell => "S2 cell",
+            Format::Alphabet => "alphabet",
+            Format::Altitude => "altitude",
+            Format::Time => "time",
+            Format::Track => "track",
+            Format::Codec => "codec string",
+            Format::SpaceTimeKey => "space-time key",
+        })
+    }
+}
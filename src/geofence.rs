@@ -0,0 +1,210 @@
+use crate::core::{bbox_int_with_precision, char_value, error_with_precision, int_to_hash};
+use crate::geometry::{in_range, polygon_contains, polygon_edges_intersect_rect};
+use crate::{encode_iter, Coord, GeohashError, GeohashTrie};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use geo_types::Polygon;
+
+const DEFAULT_REFINEMENT: usize = 2;
+
+/// A set of polygonal geofences, for finding all fences containing a point.
+///
+/// When a fence is added, its polygon is broken down into geohash cells: cells entirely inside
+/// the polygon are kept as large as possible, while cells crossing its edges are subdivided
+/// down to a precision chosen from the size of the polygon. A lookup walks the prefixes of the
+/// point's geohash through these cells, and only runs an exact point-in-polygon test when the
+/// point falls into a cell on the edge of a fence.
+///
+/// Polygons are treated as planar shapes in longitude/latitude and must not cross the
+/// antimeridian.
+///
+/// ### Examples
+///
+/// ```
+/// use geo_types::polygon;
+/// use geohash::{Coord, GeofenceSet};
+///
+/// let mut fences = GeofenceSet::new();
+/// fences
+///     .insert(
+///         "depot",
+///         polygon![(x: 2.0, y: 48.0), (x: 3.0, y: 48.0), (x: 3.0, y: 49.0), (x: 2.0, y: 49.0)],
+///     )
+///     .unwrap();
+/// fences
+///     .insert(
+///         "city",
+///         polygon![(x: 2.2, y: 48.8), (x: 2.5, y: 48.8), (x: 2.4, y: 48.9)],
+///     )
+///     .unwrap();
+///
+/// assert_eq!(fences.lookup(Coord { x: 2.35, y: 48.82 }), [&"city", &"depot"]);
+/// assert_eq!(fences.lookup(Coord { x: 2.9, y: 48.1 }), [&"depot"]);
+/// assert!(fences.lookup(Coord { x: 4.0, y: 48.5 }).is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct GeofenceSet<Id> {
+    fences: BTreeMap<Id, Fence>,
+    // for every cell, the fences it belongs to and whether it's entirely inside of them
+    cells: GeohashTrie<Vec<(Id, bool)>>,
+    refinement: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Fence {
+    polygon: Polygon<f64>,
+    cells: Vec<String>,
+}
+
+impl<Id: Ord + Clone> Default for GeofenceSet<Id> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id: Ord + Clone> GeofenceSet<Id> {
+    /// Create an empty set of fences.
+    pub fn new() -> Self {
+        Self::with_refinement(DEFAULT_REFINEMENT)
+    }
+
+    /// Create an empty set of fences whose edge cells are `levels` characters longer than the
+    /// longest geohash spanning the size of the fence.
+    ///
+    /// More levels make for fewer exact point-in-polygon tests during lookups, at the cost of
+    /// around five times as many edge cells per level.
+    pub fn with_refinement(levels: usize) -> Self {
+        GeofenceSet {
+            fences: BTreeMap::new(),
+            cells: GeohashTrie::new(),
+            refinement: levels,
+        }
+    }
+
+    /// The number of fences in the set.
+    pub fn len(&self) -> usize {
+        self.fences.len()
+    }
+
+    /// Returns `true` if the set holds no fences.
+    pub fn is_empty(&self) -> bool {
+        self.fences.is_empty()
+    }
+
+    /// The polygon of the fence with the given id.
+    pub fn get(&self, id: &Id) -> Option<&Polygon<f64>> {
+        self.fences.get(id).map(|fence| &fence.polygon)
+    }
+
+    /// Add a fence, returning the polygon of the fence it replaced if there already was one
+    /// with the same id.
+    pub fn insert(
+        &mut self,
+        id: Id,
+        polygon: Polygon<f64>,
+    ) -> Result<Option<Polygon<f64>>, GeohashError> {
+        if let Some(c) = core::iter::once(polygon.exterior())
+            .chain(polygon.interiors())
+            .flat_map(|ring| ring.coords())
+            .find(|c| !in_range(**c))
+        {
+            return Err(GeohashError::InvalidCoordinateRange(*c));
+        }
+        let previous = self.remove(&id);
+
+        let max_len = self.max_len(&polygon);
+        let mut cells = Vec::new();
+        let mut stack: Vec<(u64, u32)> = (0..32).map(|i| (i, 5)).collect();
+        while let Some((cell, bits)) = stack.pop() {
+            let rect = bbox_int_with_precision(cell, bits);
+            if polygon_edges_intersect_rect(&polygon, rect) {
+                if bits / 5 < max_len as u32 {
+                    stack.extend((0..32).map(|i| (cell << 5 | i, bits + 5)));
+                } else {
+                    cells.push((cell, bits, false));
+                }
+            } else if polygon_contains(&polygon, rect.center()) {
+                cells.push((cell, bits, true));
+            }
+        }
+
+        let mut hashes = Vec::with_capacity(cells.len());
+        for (cell, bits, interior) in cells {
+            let hash = int_to_hash(cell, bits as usize / 5);
+            self.cells
+                .get_or_insert_with(&hash, Vec::new)?
+                .push((id.clone(), interior));
+            hashes.push(hash);
+        }
+        self.fences.insert(
+            id,
+            Fence {
+                polygon,
+                cells: hashes,
+            },
+        );
+        Ok(previous)
+    }
+
+    /// Remove a fence, returning its polygon.
+    pub fn remove(&mut self, id: &Id) -> Option<Polygon<f64>> {
+        let fence = self.fences.remove(id)?;
+        for hash in &fence.cells {
+            if let Some(entries) = self.cells.get_mut(hash) {
+                entries.retain(|(other, _)| other != id);
+                if entries.is_empty() {
+                    self.cells.remove(hash);
+                }
+            }
+        }
+        Some(fence.polygon)
+    }
+
+    /// The ids of all fences containing the coordinate, in order.
+    pub fn lookup(&self, c: Coord<f64>) -> Vec<&Id> {
+        let mut found = Vec::new();
+        let codes = match encode_iter(c) {
+            Ok(chars) => chars.filter_map(|ch| char_value(ch).ok()),
+            Err(_) => return found,
+        };
+        self.cells.for_each_prefix(codes, |entries| {
+            for (id, interior) in entries {
+                if *interior || polygon_contains(&self.fences[id].polygon, c) {
+                    found.push(id);
+                }
+            }
+        });
+        found.sort();
+        found
+    }
+
+    // the geohash length of the edge cells of a polygon
+    fn max_len(&self, polygon: &Polygon<f64>) -> usize {
+        let mut coords = polygon.exterior().coords();
+        let first = match coords.next() {
+            Some(c) => *c,
+            None => return 1,
+        };
+        let (min, max) = coords.fold((first, first), |(min, max), c| {
+            (
+                Coord {
+                    x: min.x.min(c.x),
+                    y: min.y.min(c.y),
+                },
+                Coord {
+                    x: max.x.max(c.x),
+                    y: max.y.max(c.y),
+                },
+            )
+        });
+        let spanning = (1..=12)
+            .rev()
+            .find(|&len| {
+                let (height, width) = error_with_precision(len as u32 * 5);
+                height >= max.y - min.y && width >= max.x - min.x
+            })
+            .unwrap_or(1);
+        (spanning + self.refinement).min(12)
+    }
+}
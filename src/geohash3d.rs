@@ -0,0 +1,281 @@
+use crate::core::{hash_to_int, int_to_grid, int_to_hash, spread3, squash3, value_char};
+use crate::{encode_int, Coord, Direction, Format, GeohashError, Rect};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use libm::{floor, ldexp};
+
+// 3D hashes interleave a longitude, a latitude and an altitude bit, in that order, so that the
+// first two of every three bits follow the same grid as 2D geohashes. Each axis has at most 21
+// bits, which fills 63 bits of an integer hash.
+const MAX_BITS: u32 = 63;
+const AXIS_BITS: u32 = 21;
+
+/// Encoding of positions in latitude, longitude and altitude into 3D geohashes, within a fixed
+/// range of altitudes.
+///
+/// 3D geohashes are written with the same alphabet as 2D geohashes, and keep their prefix
+/// property: every character divides a cell into 32 smaller cells, alternately splitting
+/// longitude, latitude and altitude in halves.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{Coord, Geohash3D};
+///
+/// let airspace = Geohash3D::new(-500.0, 20_000.0).unwrap();
+/// let c = Coord { x: -120.6623, y: 35.3003 };
+///
+/// let hash = airspace.encode(c, 120.0, 8).unwrap();
+/// assert_eq!(hash, "8dn7k8zk");
+///
+/// let bbox = airspace.decode_bbox(&hash).unwrap();
+/// assert!(bbox.contains(c, 120.0));
+/// assert!(bbox.max_alt - bbox.min_alt < 2.6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geohash3D {
+    min_alt: f64,
+    max_alt: f64,
+}
+
+/// The bounding box of a 3D geohash, with altitudes in the unit of its [`Geohash3D`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bbox3D {
+    pub rect: Rect<f64>,
+    pub min_alt: f64,
+    pub max_alt: f64,
+}
+
+impl Bbox3D {
+    /// The coordinate and altitude at the center of the box.
+    pub fn center(&self) -> (Coord<f64>, f64) {
+        (self.rect.center(), (self.min_alt + self.max_alt) / 2.0)
+    }
+
+    /// Whether the box contains a position, boundaries included.
+    pub fn contains(&self, c: Coord<f64>, alt: f64) -> bool {
+        let (min, max) = (self.rect.min(), self.rect.max());
+        (min.x..=max.x).contains(&c.x)
+            && (min.y..=max.y).contains(&c.y)
+            && (self.min_alt..=self.max_alt).contains(&alt)
+    }
+}
+
+impl Default for Geohash3D {
+    /// Altitudes from 500 m below to 20 km above sea level, in meters.
+    fn default() -> Self {
+        Geohash3D {
+            min_alt: -500.0,
+            max_alt: 20_000.0,
+        }
+    }
+}
+
+impl Geohash3D {
+    /// Create an encoding for altitudes between `min_alt` and `max_alt`, in any unit.
+    pub fn new(min_alt: f64, max_alt: f64) -> Result<Geohash3D, GeohashError> {
+        if !min_alt.is_finite() || !max_alt.is_finite() || min_alt >= max_alt {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Altitude,
+                reason: format!("empty range {}..{}", min_alt, max_alt),
+            });
+        }
+        Ok(Geohash3D { min_alt, max_alt })
+    }
+
+    /// The lowest altitude of the encoding.
+    pub fn min_alt(&self) -> f64 {
+        self.min_alt
+    }
+
+    /// The highest altitude of the encoding.
+    pub fn max_alt(&self) -> f64 {
+        self.max_alt
+    }
+
+    /// Encode a coordinate and altitude to an integer 3D geohash of `bits` bits, between 1
+    /// and 63.
+    pub fn encode_int(&self, c: Coord<f64>, alt: f64, bits: u32) -> Result<u64, GeohashError> {
+        if !(1..=MAX_BITS).contains(&bits) {
+            return Err(GeohashError::InvalidBits(bits));
+        }
+        if !(self.min_alt..=self.max_alt).contains(&alt) {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Altitude,
+                reason: format!("{} is outside of {}..={}", alt, self.min_alt, self.max_alt),
+            });
+        }
+        // the same rows and columns as 2D geohashes
+        let (lat_idx, lon_idx) = int_to_grid(encode_int(c, 2 * AXIS_BITS)?, 2 * AXIS_BITS);
+        let cells = (1u64 << AXIS_BITS) as f64;
+        let alt_idx = floor((alt - self.min_alt) / (self.max_alt - self.min_alt) * cells)
+            .min(cells - 1.0) as u32;
+        Ok(interleave3(lon_idx, lat_idx, alt_idx) >> (MAX_BITS - bits))
+    }
+
+    /// Decode an integer 3D geohash of `bits` bits into its bounding box.
+    pub fn decode_bbox_int(&self, int_hash: u64, bits: u32) -> Result<Bbox3D, GeohashError> {
+        if !(1..=MAX_BITS).contains(&bits) {
+            return Err(GeohashError::InvalidBits(bits));
+        }
+        let (lon_bits, lat_bits, alt_bits) = axis_bits(bits);
+        let (lon_idx, lat_idx, alt_idx) = int_to_grid3(int_hash & (u64::MAX >> (64 - bits)), bits);
+        let width = ldexp(360.0, -(lon_bits as i32));
+        let height = ldexp(180.0, -(lat_bits as i32));
+        let depth = ldexp(self.max_alt - self.min_alt, -(alt_bits as i32));
+        Ok(Bbox3D {
+            rect: Rect::new(
+                Coord {
+                    x: lon_idx as f64 * width - 180.0,
+                    y: lat_idx as f64 * height - 90.0,
+                },
+                Coord {
+                    x: (lon_idx + 1) as f64 * width - 180.0,
+                    y: (lat_idx + 1) as f64 * height - 90.0,
+                },
+            ),
+            min_alt: self.min_alt + alt_idx as f64 * depth,
+            max_alt: self.min_alt + (alt_idx + 1) as f64 * depth,
+        })
+    }
+
+    /// Encode a coordinate and altitude to a 3D geohash of `len` characters.
+    pub fn encode(&self, c: Coord<f64>, alt: f64, len: usize) -> Result<String, GeohashError> {
+        if !(1..=12).contains(&len) {
+            return Err(GeohashError::InvalidLength(len));
+        }
+        Ok(int_to_hash(self.encode_int(c, alt, len as u32 * 5)?, len))
+    }
+
+    /// Decode a 3D geohash into its bounding box.
+    pub fn decode_bbox(&self, hash_str: &str) -> Result<Bbox3D, GeohashError> {
+        self.decode_bbox_int(hash_to_int(hash_str)?, hash_str.len() as u32 * 5)
+    }
+
+    /// Find the neighboring 3D geohash in the given horizontal direction, if any, and `dalt`
+    /// cells up or down.
+    ///
+    /// Like [`neighbor`](crate::neighbor), cells wrap around the world horizontally, but
+    /// there is no neighbor above the highest or below the lowest altitude.
+    ///
+    /// ### Examples
+    ///
+    /// ```
+    /// use geohash::{Direction, Geohash3D};
+    ///
+    /// let airspace = Geohash3D::default();
+    /// let above = airspace.neighbor("8dn7k", None, 1).unwrap().unwrap();
+    ///
+    /// let (cell, up) = (airspace.decode_bbox("8dn7k").unwrap(), airspace.decode_bbox(&above).unwrap());
+    /// assert_eq!(cell.rect, up.rect);
+    /// assert_eq!(cell.max_alt, up.min_alt);
+    ///
+    /// assert_eq!(airspace.neighbor("zzzzz", Some(Direction::E), 1).unwrap(), None);
+    /// ```
+    pub fn neighbor(
+        &self,
+        hash_str: &str,
+        direction: Option<Direction>,
+        dalt: i64,
+    ) -> Result<Option<String>, GeohashError> {
+        let bits = hash_str.len() as u32 * 5;
+        let (lon_bits, lat_bits, alt_bits) = axis_bits(bits);
+        let (lon_idx, lat_idx, alt_idx) = int_to_grid3(hash_to_int(hash_str)?, bits);
+        let (dlat, dlon) = direction.map_or((0.0, 0.0), Direction::to_tuple);
+        let step = |idx: u32, d: f64, n: u32| (idx as i64 + d as i64).rem_euclid(1 << n) as u32;
+        let alt_idx = alt_idx as i64 + dalt;
+        if !(0..1 << alt_bits).contains(&alt_idx) {
+            return Ok(None);
+        }
+        let int_hash = grid3_to_int(
+            step(lon_idx, dlon, lon_bits),
+            step(lat_idx, dlat, lat_bits),
+            alt_idx as u32,
+            bits,
+        );
+        Ok(Some(int_to_hash(int_hash, hash_str.len())))
+    }
+
+    /// Find the up to 26 neighbors of a 3D geohash: the 8 cells around it at the same
+    /// altitude, and the 9 cells above and below it, if any.
+    pub fn neighbors(&self, hash_str: &str) -> Result<Vec<String>, GeohashError> {
+        let directions = [
+            None,
+            Some(Direction::SW),
+            Some(Direction::S),
+            Some(Direction::SE),
+            Some(Direction::W),
+            Some(Direction::E),
+            Some(Direction::NW),
+            Some(Direction::N),
+            Some(Direction::NE),
+        ];
+        let mut neighbors = Vec::with_capacity(26);
+        for dalt in [-1, 0, 1] {
+            for direction in directions.iter() {
+                if dalt == 0 && direction.is_none() {
+                    continue;
+                }
+                if let Some(neighbor) = self.neighbor(hash_str, *direction, dalt)? {
+                    neighbors.push(neighbor);
+                }
+            }
+        }
+        Ok(neighbors)
+    }
+
+    /// The 3D geohash one character shorter, whose cell contains the cell of `hash_str`.
+    pub fn parent(&self, hash_str: &str) -> Result<String, GeohashError> {
+        hash_to_int(hash_str)?;
+        if hash_str.len() == 1 {
+            return Err(GeohashError::InvalidHash(
+                "Hash string of a single character has no parent".into(),
+            ));
+        }
+        Ok(hash_str[..hash_str.len() - 1].into())
+    }
+
+    /// The 32 3D geohashes one character longer, whose cells divide the cell of `hash_str`.
+    pub fn children(&self, hash_str: &str) -> Result<Vec<String>, GeohashError> {
+        hash_to_int(hash_str)?;
+        if hash_str.len() >= 12 {
+            return Err(GeohashError::InvalidLength(hash_str.len() + 1));
+        }
+        Ok((0..32)
+            .map(|v| format!("{}{}", hash_str, value_char(v)))
+            .collect())
+    }
+}
+
+// the number of longitude, latitude and altitude bits in a 3D hash of `bits` bits, the first
+// axes take the extra bits
+fn axis_bits(bits: u32) -> (u32, u32, u32) {
+    (bits.div_ceil(3), (bits + 1) / 3, bits / 3)
+}
+
+// interleaves indices of 21 bits into a 63 bit hash
+fn interleave3(lon_idx: u32, lat_idx: u32, alt_idx: u32) -> u64 {
+    (spread3(lon_idx) << 2) | (spread3(lat_idx) << 1) | spread3(alt_idx)
+}
+
+// splits a 3D hash of `bits` bits into its indices along each axis, like `int_to_grid`
+fn int_to_grid3(int_hash: u64, bits: u32) -> (u32, u32, u32) {
+    let (lon_bits, lat_bits, alt_bits) = axis_bits(bits);
+    let full = int_hash << (MAX_BITS - bits);
+    (
+        squash3(full >> 2) >> (AXIS_BITS - lon_bits),
+        squash3(full >> 1) >> (AXIS_BITS - lat_bits),
+        squash3(full) >> (AXIS_BITS - alt_bits),
+    )
+}
+
+// the inverse of `int_to_grid3`
+fn grid3_to_int(lon_idx: u32, lat_idx: u32, alt_idx: u32, bits: u32) -> u64 {
+    let (lon_bits, lat_bits, alt_bits) = axis_bits(bits);
+    interleave3(
+        lon_idx << (AXIS_BITS - lon_bits),
+        lat_idx << (AXIS_BITS - lat_bits),
+        alt_idx << (AXIS_BITS - alt_bits),
+    ) >> (MAX_BITS - bits)
+}
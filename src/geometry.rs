@@ -0,0 +1,120 @@
+use crate::{Coord, Rect};
+use alloc::vec::Vec;
+use geo_types::{Geometry, LineString, Polygon};
+
+// planar geometry helpers in longitude/latitude space, none of them wrap around the antimeridian
+
+/// Whether the point is inside the polygon, using the even-odd rule over all of its rings so
+/// that points inside holes are outside.
+pub(crate) fn polygon_contains(polygon: &Polygon<f64>, c: Coord<f64>) -> bool {
+    core::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .filter(|ring| ring_crossings_odd(ring, c))
+        .count()
+        % 2
+        == 1
+}
+
+// casts a ray from `c` towards positive x and checks if it crosses the ring an odd number of
+// times
+fn ring_crossings_odd(ring: &LineString<f64>, c: Coord<f64>) -> bool {
+    let mut inside = false;
+    for line in ring.lines() {
+        let (a, b) = (line.start, line.end);
+        if (a.y > c.y) != (b.y > c.y) {
+            let x = a.x + (c.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if c.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Whether any part of the segment from `a` to `b` lies inside the closed rectangle, using
+/// Liang-Barsky clipping.
+pub(crate) fn segment_intersects_rect(a: Coord<f64>, b: Coord<f64>, rect: Rect<f64>) -> bool {
+    let (min, max) = (rect.min(), rect.max());
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let (mut t0, mut t1) = (0f64, 1f64);
+    for (p, q) in [
+        (-dx, a.x - min.x),
+        (dx, max.x - a.x),
+        (-dy, a.y - min.y),
+        (dy, max.y - a.y),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return false;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+            if t0 > t1 {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Whether any edge of the polygon, including the edges of its holes, touches the rectangle.
+pub(crate) fn polygon_edges_intersect_rect(polygon: &Polygon<f64>, rect: Rect<f64>) -> bool {
+    core::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .flat_map(|ring| ring.lines())
+        .any(|line| segment_intersects_rect(line.start, line.end, rect))
+}
+
+/// Whether both coordinates of the point are within the valid longitude and latitude ranges.
+pub(crate) fn in_range(c: Coord<f64>) -> bool {
+    (-180.0..=180.0).contains(&c.x) && (-90.0..=90.0).contains(&c.y)
+}
+
+/// The smallest rectangle containing all coordinates of the geometry, `None` if it has none.
+pub(crate) fn bounding_rect(geometry: &Geometry<f64>) -> Option<Rect<f64>> {
+    let mut coords = Vec::new();
+    push_coords(geometry, &mut coords);
+    let first = *coords.first()?;
+    Some(coords.iter().fold(Rect::new(first, first), |rect, c| {
+        let (min, max) = (rect.min(), rect.max());
+        Rect::new(
+            Coord {
+                x: min.x.min(c.x),
+                y: min.y.min(c.y),
+            },
+            Coord {
+                x: max.x.max(c.x),
+                y: max.y.max(c.y),
+            },
+        )
+    }))
+}
+
+fn push_coords(geometry: &Geometry<f64>, coords: &mut Vec<Coord<f64>>) {
+    match geometry {
+        Geometry::Point(point) => coords.push(point.0),
+        Geometry::Line(line) => coords.extend([line.start, line.end]),
+        Geometry::LineString(line_string) => coords.extend(&line_string.0),
+        // the holes are inside the exterior ring
+        Geometry::Polygon(polygon) => coords.extend(&polygon.exterior().0),
+        Geometry::MultiPoint(points) => coords.extend(points.0.iter().map(|point| point.0)),
+        Geometry::MultiLineString(lines) => {
+            coords.extend(lines.0.iter().flat_map(|line_string| &line_string.0))
+        }
+        Geometry::MultiPolygon(polygons) => {
+            coords.extend(polygons.0.iter().flat_map(|polygon| &polygon.exterior().0))
+        }
+        Geometry::GeometryCollection(collection) => {
+            for geometry in &collection.0 {
+                push_coords(geometry, coords);
+            }
+        }
+        Geometry::Rect(rect) => coords.extend([rect.min(), rect.max()]),
+        Geometry::Triangle(triangle) => coords.extend(triangle.to_array()),
+    }
+}
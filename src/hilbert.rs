@@ -0,0 +1,282 @@
+use crate::core::{
+    error_with_precision, grid_bits, grid_to_int, hash_to_int, int_to_grid, int_to_hash,
+};
+use crate::ranges::refine_ranges;
+use crate::{
+    decode_bbox_int, encode_int, neighbor, Coord, Direction, GeohashError, Neighbors, Rect,
+};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+// Hilbert keys use the same grid of cells as geohashes of the same number of bits. When the
+// number of bits is odd, there are twice as many columns as rows: the first bit then picks the
+// western or eastern half of the world, and each half is a square traversed by the Hilbert
+// curve. The curve of the western half ends in its south-east corner, right next to where the
+// curve of the eastern half starts, so the whole curve stays continuous.
+
+fn check_bits(bits: u32) -> Result<(), GeohashError> {
+    if !(1..=64).contains(&bits) {
+        return Err(GeohashError::InvalidBits(bits));
+    }
+    Ok(())
+}
+
+// the position along the Hilbert curve over a square of 2^order cells a side, starting in the
+// south-west corner and ending in the south-east one, of the cell in column x and row y
+fn xy_to_d(order: u32, mut x: u64, mut y: u64) -> u64 {
+    let n = 1u64 << order;
+    let mut d = 0;
+    let mut s = n >> 1;
+    while s > 0 {
+        let rx = (x & s != 0) as u64;
+        let ry = (y & s != 0) as u64;
+        d += s * s * ((3 * rx) ^ ry);
+        rotate(n, &mut x, &mut y, rx, ry);
+        s >>= 1;
+    }
+    d
+}
+
+// the inverse of `xy_to_d`
+fn d_to_xy(order: u32, d: u64) -> (u64, u64) {
+    let n = 1u64 << order;
+    let (mut x, mut y) = (0, 0);
+    let mut t = d;
+    let mut s = 1;
+    while s < n {
+        let rx = 1 & (t >> 1);
+        let ry = 1 & (t ^ rx);
+        rotate(s, &mut x, &mut y, rx, ry);
+        x += s * rx;
+        y += s * ry;
+        t >>= 2;
+        s <<= 1;
+    }
+    (x, y)
+}
+
+// turns a quadrant into the orientation of the curve through it
+fn rotate(n: u64, x: &mut u64, y: &mut u64, rx: u64, ry: u64) {
+    if ry == 0 {
+        if rx == 1 {
+            *x = n - 1 - *x;
+            *y = n - 1 - *y;
+        }
+        core::mem::swap(x, y);
+    }
+}
+
+fn grid_to_key(lat_idx: u32, lon_idx: u32, bits: u32) -> u64 {
+    let (order, lon_bits) = grid_bits(bits);
+    let mask = (1u64 << order) - 1;
+    let d = xy_to_d(order, lon_idx as u64 & mask, lat_idx as u64);
+    if lon_bits > order {
+        (lon_idx as u64 >> order) << (2 * order) | d
+    } else {
+        d
+    }
+}
+
+fn key_to_grid(key: u64, bits: u32) -> (u32, u32) {
+    let (order, _) = grid_bits(bits);
+    let mask = u64::MAX.checked_shr(64 - 2 * order).unwrap_or(0);
+    let (x, y) = d_to_xy(order, key & mask);
+    let half = if 2 * order < bits {
+        key >> (2 * order)
+    } else {
+        0
+    };
+    (y as u32, (half << order | x) as u32)
+}
+
+/// Convert an integer geohash of `bits` bits into the Hilbert key of the same cell.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{hilbert_to_z_order, z_order_to_hilbert};
+///
+/// let key = z_order_to_hilbert(0b01001_10110, 10).unwrap();
+/// assert_eq!(hilbert_to_z_order(key, 10).unwrap(), 0b01001_10110);
+/// ```
+pub fn z_order_to_hilbert(int_hash: u64, bits: u32) -> Result<u64, GeohashError> {
+    check_bits(bits)?;
+    let (lat_idx, lon_idx) = int_to_grid(int_hash & (u64::MAX >> (64 - bits)), bits);
+    Ok(grid_to_key(lat_idx, lon_idx, bits))
+}
+
+/// Convert a Hilbert key of `bits` bits into the integer geohash of the same cell.
+pub fn hilbert_to_z_order(key: u64, bits: u32) -> Result<u64, GeohashError> {
+    check_bits(bits)?;
+    let (lat_idx, lon_idx) = key_to_grid(key & (u64::MAX >> (64 - bits)), bits);
+    Ok(grid_to_int(lat_idx, lon_idx, bits))
+}
+
+/// Encode a coordinate to a Hilbert key of `bits` bits.
+///
+/// Keys index the same cells as [`encode_int`], but number them along a Hilbert curve
+/// instead of a Z-order curve, so that cells next to each other are more often close
+/// together in key order as well.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{encode_hilbert_int, Coord};
+///
+/// let key = encode_hilbert_int(Coord { x: -120.6623, y: 35.3003 }, 25).unwrap();
+/// assert_eq!(key, 7_642_139);
+/// ```
+pub fn encode_hilbert_int(c: Coord<f64>, bits: u32) -> Result<u64, GeohashError> {
+    z_order_to_hilbert(encode_int(c, bits)?, bits)
+}
+
+/// Decode a Hilbert key of `bits` bits into the bounding box of its cell.
+pub fn decode_hilbert_bbox_int(key: u64, bits: u32) -> Result<Rect<f64>, GeohashError> {
+    decode_bbox_int(hilbert_to_z_order(key, bits)?, bits)
+}
+
+/// Encode a coordinate to a Hilbert key of `len` characters, written with the geohash
+/// alphabet.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{encode_hilbert, Coord};
+///
+/// assert_eq!(encode_hilbert(Coord { x: -120.6623, y: 35.3003 }, 5).unwrap(), "7970v");
+/// ```
+pub fn encode_hilbert(c: Coord<f64>, len: usize) -> Result<String, GeohashError> {
+    if !(1..=12).contains(&len) {
+        return Err(GeohashError::InvalidLength(len));
+    }
+    Ok(int_to_hash(encode_hilbert_int(c, len as u32 * 5)?, len))
+}
+
+/// Decode a Hilbert key written with the geohash alphabet into the bounding box of its cell.
+pub fn decode_hilbert_bbox(key: &str) -> Result<Rect<f64>, GeohashError> {
+    decode_hilbert_bbox_int(hash_to_int(key)?, key.len() as u32 * 5)
+}
+
+/// Convert a geohash into the Hilbert key of the same cell, written with the geohash
+/// alphabet.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{hash_to_hilbert, hilbert_to_hash};
+///
+/// assert_eq!(hash_to_hilbert("9q60y").unwrap(), "7970v");
+/// assert_eq!(hilbert_to_hash("7970v").unwrap(), "9q60y");
+/// ```
+pub fn hash_to_hilbert(hash_str: &str) -> Result<String, GeohashError> {
+    let bits = hash_str.len() as u32 * 5;
+    let key = z_order_to_hilbert(hash_to_int(hash_str)?, bits)?;
+    Ok(int_to_hash(key, hash_str.len()))
+}
+
+/// Convert a Hilbert key written with the geohash alphabet into the geohash of the same cell.
+pub fn hilbert_to_hash(key: &str) -> Result<String, GeohashError> {
+    let bits = key.len() as u32 * 5;
+    let int_hash = hilbert_to_z_order(hash_to_int(key)?, bits)?;
+    Ok(int_to_hash(int_hash, key.len()))
+}
+
+/// Find the neighboring Hilbert key in the given direction, following the same rules as
+/// [`neighbor`].
+pub fn hilbert_neighbor(key: &str, direction: Direction) -> Result<String, GeohashError> {
+    hash_to_hilbert(&neighbor(&hilbert_to_hash(key)?, direction)?)
+}
+
+/// Find all neighboring Hilbert keys of a Hilbert key.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{hilbert_neighbors, hash_to_hilbert, neighbors};
+///
+/// let around = hilbert_neighbors("7970v").unwrap();
+/// assert_eq!(around.n, hash_to_hilbert(&neighbors("9q60y").unwrap().n).unwrap());
+/// ```
+pub fn hilbert_neighbors(key: &str) -> Result<Neighbors, GeohashError> {
+    Ok(Neighbors {
+        sw: hilbert_neighbor(key, Direction::SW)?,
+        s: hilbert_neighbor(key, Direction::S)?,
+        se: hilbert_neighbor(key, Direction::SE)?,
+        w: hilbert_neighbor(key, Direction::W)?,
+        e: hilbert_neighbor(key, Direction::E)?,
+        nw: hilbert_neighbor(key, Direction::NW)?,
+        n: hilbert_neighbor(key, Direction::N)?,
+        ne: hilbert_neighbor(key, Direction::NE)?,
+    })
+}
+
+// the bounding box of the cells whose Hilbert keys of `bits` bits start with a `depth` bit
+// prefix
+fn prefix_bbox(prefix: u64, depth: u32, bits: u32) -> Rect<f64> {
+    let (order, lon_bits) = grid_bits(bits);
+    let extra = lon_bits - order;
+    if depth < extra || (depth - extra) % 2 == 1 {
+        // an odd prefix of the curve covers two neighboring squares
+        let (a, b) = (
+            prefix_bbox(prefix << 1, depth + 1, bits),
+            prefix_bbox(prefix << 1 | 1, depth + 1, bits),
+        );
+        return Rect::new(
+            Coord {
+                x: a.min().x.min(b.min().x),
+                y: a.min().y.min(b.min().y),
+            },
+            Coord {
+                x: a.max().x.max(b.max().x),
+                y: a.max().y.max(b.max().y),
+            },
+        );
+    }
+    // an even prefix covers a square, found from its first cell
+    let size = 1u64 << (order - (depth - extra) / 2);
+    let (lat_idx, lon_idx) = key_to_grid(prefix.checked_shl(bits - depth).unwrap_or(0), bits);
+    let (lat0, lon0) = (lat_idx as u64 & !(size - 1), lon_idx as u64 & !(size - 1));
+    let (lat_err, lon_err) = error_with_precision(bits);
+    Rect::new(
+        Coord {
+            x: lon0 as f64 * lon_err - 180.0,
+            y: lat0 as f64 * lat_err - 90.0,
+        },
+        Coord {
+            x: (lon0 + size) as f64 * lon_err - 180.0,
+            y: (lat0 + size) as f64 * lat_err - 90.0,
+        },
+    )
+}
+
+/// Decompose a rectangle into ranges of Hilbert keys of `bits` bits, like [`rect_to_ranges`]
+/// does for integer geohashes.
+///
+/// Thanks to the better locality of the Hilbert curve, the same rectangle usually needs fewer
+/// ranges, or fewer false positives for the same number of ranges. The ends of the ranges are
+/// inclusive for the same reason, so that the last key of 64 bits can be covered.
+///
+/// [`rect_to_ranges`]: crate::rect_to_ranges
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{encode_hilbert_int, hilbert_rect_to_ranges, Coord, Rect};
+///
+/// let rect = Rect::new(Coord { x: -122.5, y: 37.7 }, Coord { x: -122.3, y: 37.8 });
+/// let ranges = hilbert_rect_to_ranges(rect, 40, 8).unwrap();
+///
+/// let key = encode_hilbert_int(Coord { x: -122.4, y: 37.75 }, 40).unwrap();
+/// assert!(ranges.iter().any(|r| r.contains(&key)));
+/// ```
+pub fn hilbert_rect_to_ranges(
+    rect: Rect<f64>,
+    bits: u32,
+    max_ranges: usize,
+) -> Result<Vec<RangeInclusive<u64>>, GeohashError> {
+    check_bits(bits)?;
+    refine_ranges(rect, bits, max_ranges, |prefix, depth| {
+        prefix_bbox(prefix, depth, bits)
+    })
+}
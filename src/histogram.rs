@@ -0,0 +1,233 @@
+use crate::neighbors::block;
+use crate::{decode_bbox, encode, Coord, GeohashError};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// The number and the total weight of the points of a cell of a [`GeohashHistogram`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CellStats {
+    /// The number of points.
+    pub count: u64,
+    /// The total weight of the points.
+    pub sum: f64,
+}
+
+impl CellStats {
+    /// The mean weight of the points, `NaN` without points.
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    fn add(&mut self, other: &CellStats) {
+        self.count += other.count;
+        self.sum += other.sum;
+    }
+}
+
+/// An accumulator of weighted points, counting them and summing their weights per geohash of a
+/// fixed length.
+///
+/// Histograms of the same length built from parts of the data can be [`merge`]d, in any order,
+/// into the histogram of all of it, so they can be computed in a map-reduce style. Only the
+/// cells with points are stored.
+///
+/// [`merge`]: GeohashHistogram::merge
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{Coord, GeohashHistogram};
+///
+/// let mut histogram = GeohashHistogram::new(5).unwrap();
+/// histogram.add(Coord { x: 2.2945, y: 48.8582 }, 3.0).unwrap();
+/// histogram.add(Coord { x: 2.2950, y: 48.8590 }, 5.0).unwrap();
+/// histogram.add(Coord { x: 2.3522, y: 48.8566 }, 1.0).unwrap();
+///
+/// let stats = histogram.get("u09tu").unwrap();
+/// assert_eq!((stats.count, stats.sum, stats.mean()), (2, 8.0, 4.0));
+///
+/// let mut other = GeohashHistogram::new(5).unwrap();
+/// other.add(Coord { x: 2.2948, y: 48.8585 }, 4.0).unwrap();
+/// histogram.merge(&other).unwrap();
+/// assert_eq!(histogram.get("u09tu").unwrap().count, 3);
+///
+/// let csv = histogram.to_csv();
+/// assert_eq!(csv.lines().next(), Some("geohash,count,sum,mean"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeohashHistogram {
+    precision: usize,
+    cells: BTreeMap<String, CellStats>,
+}
+
+impl GeohashHistogram {
+    /// Create an empty histogram over the geohashes of length `precision`.
+    pub fn new(precision: usize) -> Result<Self, GeohashError> {
+        if !(1..=12).contains(&precision) {
+            return Err(GeohashError::InvalidLength(precision));
+        }
+        Ok(GeohashHistogram {
+            precision,
+            cells: BTreeMap::new(),
+        })
+    }
+
+    /// The length of the geohashes of the cells.
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    /// The number of cells with points.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether no point was added.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Add a point with a weight, which must be finite.
+    pub fn add(&mut self, c: Coord<f64>, weight: f64) -> Result<(), GeohashError> {
+        if !weight.is_finite() {
+            return Err(GeohashError::InvalidWeight(weight));
+        }
+        let stats = self.cells.entry(encode(c, self.precision)?).or_default();
+        stats.add(&CellStats {
+            count: 1,
+            sum: weight,
+        });
+        Ok(())
+    }
+
+    /// Add the points of another histogram with the same precision.
+    pub fn merge(&mut self, other: &GeohashHistogram) -> Result<(), GeohashError> {
+        if other.precision != self.precision {
+            return Err(GeohashError::PrecisionMismatch {
+                expected: self.precision,
+                found: other.precision,
+            });
+        }
+        for (hash, stats) in &other.cells {
+            self.cells.entry(hash.clone()).or_default().add(stats);
+        }
+        Ok(())
+    }
+
+    /// The statistics of a cell, if it has points.
+    pub fn get(&self, hash_str: &str) -> Option<&CellStats> {
+        self.cells.get(hash_str)
+    }
+
+    /// The cells with points and their statistics, ordered by geohash.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &CellStats)> {
+        self.cells
+            .iter()
+            .map(|(hash, stats)| (hash.as_str(), stats))
+    }
+
+    /// The `k` cells with the largest total weight, largest first, ties ordered by geohash.
+    pub fn top_k(&self, k: usize) -> Vec<(&str, &CellStats)> {
+        let mut cells: Vec<_> = self.iter().collect();
+        // the sort is stable, so ties stay in geohash order
+        cells.sort_by(|(_, a), (_, b)| b.sum.total_cmp(&a.sum));
+        cells.truncate(k);
+        cells
+    }
+
+    /// Smooth the histogram over the cells around each cell.
+    ///
+    /// Every cell next to a cell with points gets the totals of its own points and of the
+    /// points of the 8 cells around it, so its mean is the mean weight of the points of that
+    /// block of cells. The cells around a cell wrap around the antimeridian but not around the
+    /// poles, so the cells along a pole only get the totals of the 6 cells around them.
+    pub fn smooth(&self) -> Result<GeohashHistogram, GeohashError> {
+        let mut smoothed = GeohashHistogram {
+            precision: self.precision,
+            cells: BTreeMap::new(),
+        };
+        for (hash, stats) in &self.cells {
+            for cell in block(hash)? {
+                smoothed.cells.entry(cell).or_default().add(stats);
+            }
+        }
+        Ok(smoothed)
+    }
+
+    /// Aggregate the cells into their parents of length `precision`, at most the precision of
+    /// the histogram.
+    pub fn rebucket(&self, precision: usize) -> Result<GeohashHistogram, GeohashError> {
+        if !(1..=self.precision).contains(&precision) {
+            return Err(GeohashError::InvalidLength(precision));
+        }
+        let mut coarse = GeohashHistogram::new(precision)?;
+        for (hash, stats) in &self.cells {
+            let parent = &hash[..precision];
+            match coarse.cells.get_mut(parent) {
+                Some(total) => total.add(stats),
+                None => {
+                    coarse.cells.insert(parent.into(), *stats);
+                }
+            }
+        }
+        Ok(coarse)
+    }
+
+    /// Write the cells as a GeoJSON `FeatureCollection` of rectangles, with the `geohash`,
+    /// `count`, `sum` and `mean` of each cell as properties. A sum or mean that overflowed to
+    /// infinity is written as `null`.
+    pub fn to_geojson(&self) -> String {
+        let mut json = String::from(r#"{"type":"FeatureCollection","features":["#);
+        for (i, (hash, stats)) in self.cells.iter().enumerate() {
+            let rect = decode_bbox(hash).expect("geohashes are valid");
+            let (min, max) = (rect.min(), rect.max());
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str(&format!(
+                concat!(
+                    r#"{{"type":"Feature","geometry":{{"type":"Polygon","coordinates":"#,
+                    r#"[[[{0},{1}],[{2},{1}],[{2},{3}],[{0},{3}],[{0},{1}]]]}},"#,
+                    r#""properties":{{"geohash":"{4}","count":{5},"sum":{6},"mean":{7}}}}}"#
+                ),
+                min.x,
+                min.y,
+                max.x,
+                max.y,
+                hash,
+                stats.count,
+                json_number(stats.sum),
+                json_number(stats.mean())
+            ));
+        }
+        json.push_str("]}");
+        json
+    }
+
+    /// Write the cells as CSV, with a `geohash,count,sum,mean` header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("geohash,count,sum,mean\n");
+        for (hash, stats) in &self.cells {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                hash,
+                stats.count,
+                stats.sum,
+                stats.mean()
+            ));
+        }
+        csv
+    }
+}
+
+// a number in JSON, which has no infinities or NaN, so that a sum overflowing to infinity is
+// written as `null`
+fn json_number(v: f64) -> String {
+    if v.is_finite() {
+        format!("{}", v)
+    } else {
+        String::from("null")
+    }
+}
@@ -0,0 +1,229 @@
+use crate::core::{encode_int, error_with_precision, grid_bits, grid_to_int, int_to_grid};
+use crate::distance::{haversine, meridian_distance, METERS_PER_DEGREE};
+use crate::{Coord, GeohashError};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+// every point is stored under its full precision (length 12) integer hash
+const KEY_BITS: u32 = 60;
+
+// query results, as `(coordinate, value, distance)` tuples
+type Hits<'a, T> = Vec<(Coord<f64>, &'a T, f64)>;
+
+/// An in-memory spatial index of values keyed by the geohash of their coordinate.
+///
+/// Points are stored in a sorted map of integer geohashes, so that all points inside a geohash
+/// cell are found with a single range scan. Queries search rings of cells outward from the cell
+/// containing the query point, and only stop once no unvisited cell can hold a closer point. All
+/// distances are great-circle distances in meters between the query point and the stored
+/// coordinates.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{Coord, GeohashIndex};
+///
+/// let mut index = GeohashIndex::new();
+/// index.insert(Coord { x: 2.3522, y: 48.8566 }, "Paris").unwrap();
+/// index.insert(Coord { x: 4.8357, y: 45.7640 }, "Lyon").unwrap();
+/// index.insert(Coord { x: -0.1276, y: 51.5072 }, "London").unwrap();
+///
+/// // the two cities closest to Orléans
+/// let nearest = index.knn(Coord { x: 1.9093, y: 47.9030 }, 2).unwrap();
+/// let names: Vec<_> = nearest.iter().map(|&(_, name, _)| *name).collect();
+/// assert_eq!(names, ["Paris", "Lyon"]);
+///
+/// // everything within 350km of Paris
+/// let close = index.within_radius(Coord { x: 2.3522, y: 48.8566 }, 350_000.0).unwrap();
+/// assert_eq!(close.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GeohashIndex<T> {
+    cells: BTreeMap<u64, Vec<(Coord<f64>, T)>>,
+    len: usize,
+}
+
+impl<T> Default for GeohashIndex<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> GeohashIndex<T> {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        GeohashIndex {
+            cells: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// The number of values in the index.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the index holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert a value at the given coordinate.
+    pub fn insert(&mut self, c: Coord<f64>, value: T) -> Result<(), GeohashError> {
+        let key = encode_int(c, KEY_BITS)?;
+        self.cells.entry(key).or_default().push((c, value));
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove a value equal to `value` stored at exactly the coordinate `c`, returning it if
+    /// it was present.
+    pub fn remove(&mut self, c: Coord<f64>, value: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let key = encode_int(c, KEY_BITS).ok()?;
+        let entries = self.cells.get_mut(&key)?;
+        let pos = entries
+            .iter()
+            .position(|(coord, v)| *coord == c && v == value)?;
+        let (_, removed) = entries.swap_remove(pos);
+        if entries.is_empty() {
+            self.cells.remove(&key);
+        }
+        self.len -= 1;
+        Some(removed)
+    }
+
+    /// Iterate over all coordinates and values in the index, in geohash order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord<f64>, &T)> {
+        self.cells
+            .values()
+            .flat_map(|entries| entries.iter().map(|(c, v)| (*c, v)))
+    }
+
+    /// Find all values within `meters` of `center`.
+    ///
+    /// Returns `(coordinate, value, distance)` tuples ordered by increasing distance.
+    pub fn within_radius(
+        &self,
+        center: Coord<f64>,
+        meters: f64,
+    ) -> Result<Hits<'_, T>, GeohashError> {
+        let len = radius_precision(center, meters);
+        let mut hits = self.search(center, len, |_, bound| bound > meters)?;
+        hits.retain(|&(_, _, d)| d <= meters);
+        Ok(hits)
+    }
+
+    /// Find the `k` values closest to `center`.
+    ///
+    /// Returns `(coordinate, value, distance)` tuples ordered by increasing distance. Fewer than
+    /// `k` results are returned only if the index holds fewer than `k` values.
+    pub fn knn(&self, center: Coord<f64>, k: usize) -> Result<Hits<'_, T>, GeohashError> {
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        // aim for cells that hold around `k` values each, assuming an even spread, but avoid
+        // cells that are much narrower than they are tall towards the poles
+        let ratio = (self.len / k).max(1);
+        let len = ((usize::BITS - ratio.leading_zeros()) as usize / 5).clamp(1, 12);
+        let height = error_with_precision(len as u32 * 5).0 * METERS_PER_DEGREE;
+        let len = radius_precision(center, height).min(len);
+        let mut hits = self.search(center, len, |hits, bound| {
+            hits.len() >= k && hits[k - 1].2 <= bound
+        })?;
+        hits.truncate(k);
+        Ok(hits)
+    }
+
+    // visits rings of cells of length `len` around the cell containing `center` until `done`
+    // returns true for the hits found so far (sorted by distance) and a lower bound on the
+    // distance to any cell not yet visited
+    fn search<F>(
+        &self,
+        center: Coord<f64>,
+        len: usize,
+        mut done: F,
+    ) -> Result<Hits<'_, T>, GeohashError>
+    where
+        F: FnMut(&[(Coord<f64>, &T, f64)], f64) -> bool,
+    {
+        let bits = len as u32 * 5;
+        let (lat_bits, lon_bits) = grid_bits(bits);
+        let (cell_height, cell_width) = error_with_precision(bits);
+        let (rows, cols) = (1i64 << lat_bits, 1i64 << lon_bits);
+        let (lat_idx, lon_idx) = int_to_grid(encode_int(center, bits)?, bits);
+        let (lat_idx, lon_idx) = (lat_idx as i64, lon_idx as i64);
+
+        let mut visited = BTreeSet::new();
+        let mut hits = Vec::new();
+        for ring in 0.. {
+            for (dlat, dlon) in ring_offsets(ring) {
+                let lat = lat_idx + dlat;
+                if !(0..rows).contains(&lat) {
+                    continue;
+                }
+                let lon = (lon_idx + dlon).rem_euclid(cols);
+                let cell = grid_to_int(lat as u32, lon as u32, bits);
+                if !visited.insert(cell) {
+                    continue;
+                }
+                let shift = KEY_BITS - bits;
+                for entries in self.cells.range(cell << shift..(cell + 1) << shift) {
+                    for (c, v) in entries.1 {
+                        hits.push((*c, v, haversine(center, *c)));
+                    }
+                }
+            }
+            hits.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+            // the visited cells form a block around the center cell, anything outside of it is
+            // at least as far away as the nearest edge of the block
+            let south = -90.0 + (lat_idx - ring) as f64 * cell_height;
+            let north = -90.0 + (lat_idx + ring + 1) as f64 * cell_height;
+            let west = -180.0 + (lon_idx - ring) as f64 * cell_width;
+            let east = -180.0 + (lon_idx + ring + 1) as f64 * cell_width;
+            let mut bound = f64::INFINITY;
+            if south > -90.0 {
+                bound = bound.min((center.y - south) * METERS_PER_DEGREE);
+            }
+            if north < 90.0 {
+                bound = bound.min((north - center.y) * METERS_PER_DEGREE);
+            }
+            if 2 * ring + 1 < cols {
+                bound = bound
+                    .min(meridian_distance(center, center.x - west))
+                    .min(meridian_distance(center, east - center.x));
+            }
+            if bound.is_infinite() || done(&hits, bound) {
+                break;
+            }
+        }
+        Ok(hits)
+    }
+}
+
+// the offsets of the cells at chebyshev distance `ring` from a cell
+fn ring_offsets(ring: i64) -> impl Iterator<Item = (i64, i64)> {
+    (-ring..=ring).flat_map(move |dlat| {
+        let step = if dlat.abs() == ring {
+            1
+        } else {
+            (2 * ring).max(1) as usize
+        };
+        (-ring..=ring).step_by(step).map(move |dlon| (dlat, dlon))
+    })
+}
+
+// the longest geohash length whose cells around `center` are at least `meters` across
+fn radius_precision(center: Coord<f64>, meters: f64) -> usize {
+    let lat_scale = libm::cos(center.y.to_radians()).max(0.0);
+    (1..=12)
+        .rev()
+        .find(|&len| {
+            let (height, width) = error_with_precision(len as u32 * 5);
+            height * METERS_PER_DEGREE >= meters && width * METERS_PER_DEGREE * lat_scale >= meters
+        })
+        .unwrap_or(1)
+}
@@ -0,0 +1,174 @@
+use crate::core::error_with_precision;
+use crate::distance::{haversine, meridian_distance, METERS_PER_DEGREE};
+use crate::geometry::in_range;
+use crate::{encode, neighbors, Coord, GeohashError};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// join results, as `(left value, right value, distance)` tuples
+type Pairs<T, U> = Vec<(T, U, f64)>;
+
+// the cells are chosen for the points up to this latitude, points closer to the poles may
+// need more than the cells around them
+const MAX_LATITUDE: f64 = 80.0;
+
+/// Find all pairs of a left and a right value whose coordinates are within `meters` of each
+/// other.
+///
+/// The right values are bucketed by their geohash, at the longest length whose cells are at
+/// least `meters` across, so every right value close enough to a left value is in the cell of
+/// the left value or one of its [`neighbors`]. The candidates found there are then checked
+/// with their exact great-circle distance. Left values too close to the poles for the cells
+/// around them to be enough are compared to all right values.
+///
+/// Returns `(left value, right value, distance)` tuples, ordered by the position of the left
+/// value and then of the right value in their iterators.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{join_within, Coord};
+///
+/// let orders = vec![
+///     (Coord { x: 2.3522, y: 48.8566 }, "order-1"),
+///     (Coord { x: 2.2945, y: 48.8582 }, "order-2"),
+/// ];
+/// let couriers = vec![
+///     (Coord { x: 2.3499, y: 48.8530 }, "courier-a"),
+///     (Coord { x: 2.3200, y: 48.8400 }, "courier-b"),
+/// ];
+///
+/// let pairs = join_within(orders, couriers, 1000.0).unwrap();
+/// let names: Vec<_> = pairs.iter().map(|&(order, courier, _)| (order, courier)).collect();
+/// assert_eq!(names, [("order-1", "courier-a")]);
+/// ```
+pub fn join_within<T, U, L, R>(left: L, right: R, meters: f64) -> Result<Pairs<T, U>, GeohashError>
+where
+    T: Clone,
+    U: Clone,
+    L: IntoIterator<Item = (Coord<f64>, T)>,
+    R: IntoIterator<Item = (Coord<f64>, U)>,
+{
+    let join = Join::new(left, right, meters)?;
+    Ok((0..join.left.len()).flat_map(|i| join.probe(i)).collect())
+}
+
+/// The same as [`join_within`], probing the left values in parallel.
+#[cfg(feature = "rayon")]
+pub fn par_join_within<T, U, L, R>(
+    left: L,
+    right: R,
+    meters: f64,
+) -> Result<Pairs<T, U>, GeohashError>
+where
+    T: Clone + Send + Sync,
+    U: Clone + Send + Sync,
+    L: IntoIterator<Item = (Coord<f64>, T)>,
+    R: IntoIterator<Item = (Coord<f64>, U)>,
+{
+    use rayon::prelude::*;
+
+    let join = Join::new(left, right, meters)?;
+    Ok((0..join.left.len())
+        .into_par_iter()
+        .flat_map_iter(|i| join.probe(i))
+        .collect())
+}
+
+struct Join<T, U> {
+    left: Vec<(Coord<f64>, T)>,
+    right: Vec<(Coord<f64>, U)>,
+    meters: f64,
+    // the length of the geohashes of the buckets, if cells can be that large
+    len: Option<usize>,
+    // the positions of the right values in each cell
+    buckets: BTreeMap<String, Vec<usize>>,
+}
+
+impl<T: Clone, U: Clone> Join<T, U> {
+    fn new<L, R>(left: L, right: R, meters: f64) -> Result<Self, GeohashError>
+    where
+        L: IntoIterator<Item = (Coord<f64>, T)>,
+        R: IntoIterator<Item = (Coord<f64>, U)>,
+    {
+        let left: Vec<_> = left.into_iter().collect();
+        let right: Vec<_> = right.into_iter().collect();
+        if let Some(&(c, _)) = left.iter().find(|(c, _)| !in_range(*c)) {
+            return Err(GeohashError::InvalidCoordinateRange(c));
+        }
+        if let Some(&(c, _)) = right.iter().find(|(c, _)| !in_range(*c)) {
+            return Err(GeohashError::InvalidCoordinateRange(c));
+        }
+
+        let latitude = left
+            .iter()
+            .map(|(c, _)| c.y.abs())
+            .fold(0.0, f64::max)
+            .min(MAX_LATITUDE);
+        let len = (1..=12).rev().find(|&len| {
+            cells_suffice(
+                Coord {
+                    x: 0.0,
+                    y: latitude,
+                },
+                len,
+                meters,
+            )
+        });
+        let mut buckets: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        if let Some(len) = len {
+            for (i, (c, _)) in right.iter().enumerate() {
+                buckets.entry(encode(*c, len)?).or_default().push(i);
+            }
+        }
+        Ok(Join {
+            left,
+            right,
+            meters,
+            len,
+            buckets,
+        })
+    }
+
+    // the pairs of the left value `i`
+    fn probe(&self, i: usize) -> Pairs<T, U> {
+        let (c, value) = &self.left[i];
+        let candidates: Vec<usize> = match self.len {
+            Some(len) if cells_suffice(*c, len, self.meters) => {
+                let hash = encode(*c, len).expect("coordinates are checked");
+                let around = neighbors(&hash).expect("geohashes are valid");
+                let mut candidates = Vec::new();
+                for cell in [
+                    &hash, &around.n, &around.ne, &around.e, &around.se, &around.s, &around.sw,
+                    &around.w, &around.nw,
+                ] {
+                    if let Some(bucket) = self.buckets.get(cell) {
+                        candidates.extend(bucket);
+                    }
+                }
+                // in the order of the right values, without the values of cells that are
+                // neighbors in more than one direction around the poles
+                candidates.sort_unstable();
+                candidates.dedup();
+                candidates
+            }
+            _ => (0..self.right.len()).collect(),
+        };
+        candidates
+            .into_iter()
+            .filter_map(|j| {
+                let (other, other_value) = &self.right[j];
+                let distance = haversine(*c, *other);
+                (distance <= self.meters).then(|| (value.clone(), other_value.clone(), distance))
+            })
+            .collect()
+    }
+}
+
+// whether all points within `meters` of `c` are in the cell of length `len` of `c` or the
+// cells around it, which holds when the points past these cells are all further away
+pub(crate) fn cells_suffice(c: Coord<f64>, len: usize, meters: f64) -> bool {
+    let (height, width) = error_with_precision(len as u32 * 5);
+    height * METERS_PER_DEGREE >= meters && meridian_distance(c, width) >= meters
+}
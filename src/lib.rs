@@ -34,11 +34,76 @@
 
 extern crate alloc;
 
+mod area;
+mod cell_set;
+mod cluster;
+mod codec;
 mod core;
+mod distance;
+mod dwell;
+mod enclosing;
 mod error;
+mod geofence;
+mod geohash3d;
+mod geometry;
+mod hilbert;
+mod histogram;
+mod index;
+mod join;
+mod line;
+mod maidenhead;
 mod neighbors;
+mod plus_code;
+mod ranges;
+mod relation;
+mod s2;
+#[cfg(feature = "rand")]
+mod sample;
+mod sort_key;
+mod spacetime;
+mod tiles;
+mod track;
+mod trie;
+mod utm;
 
-pub use crate::core::{decode, decode_bbox, encode, encode_iter, neighbor, neighbors};
-pub use crate::error::GeohashError;
+pub use crate::area::{area_m2, perimeter_m, total_area_m2};
+pub use crate::cell_set::CellSet;
+pub use crate::cluster::{cluster_points, eps_precision, Cluster, Clustering};
+pub use crate::codec::Codec;
+pub use crate::core::{
+    decode, decode_bbox, decode_bbox_int, encode, encode_int, encode_iter, neighbor, neighbors,
+};
+pub use crate::dwell::{DwellDetector, DwellEvent};
+pub use crate::enclosing::{enclosing_cells, enclosing_hash};
+pub use crate::error::{Format, GeohashError};
+pub use crate::geofence::GeofenceSet;
+pub use crate::geohash3d::{Bbox3D, Geohash3D};
+pub use crate::hilbert::{
+    decode_hilbert_bbox, decode_hilbert_bbox_int, encode_hilbert, encode_hilbert_int,
+    hash_to_hilbert, hilbert_neighbor, hilbert_neighbors, hilbert_rect_to_ranges, hilbert_to_hash,
+    hilbert_to_z_order, z_order_to_hilbert,
+};
+pub use crate::histogram::{CellStats, GeohashHistogram};
+pub use crate::index::GeohashIndex;
+pub use crate::join::join_within;
+#[cfg(feature = "rayon")]
+pub use crate::join::par_join_within;
+pub use crate::line::{cover_line, LineCell};
+pub use crate::maidenhead::{decode_maidenhead, encode_maidenhead, maidenhead_to_hashes};
 pub use crate::neighbors::{Direction, Neighbors};
+pub use crate::plus_code::{
+    decode_plus_code, encode_plus_code, hash_to_plus_code, is_full_plus_code, is_short_plus_code,
+    is_valid_plus_code, plus_code_to_hashes, recover_plus_code, shorten_plus_code,
+};
+pub use crate::ranges::{cover_rect, rect_to_ranges, MAX_COVER_CELLS};
+pub use crate::relation::{relation, CellRelation};
+pub use crate::s2::{hash_to_s2_cells, rect_to_s2_cells, s2_cell_id, s2_cell_to_hashes};
+#[cfg(feature = "rand")]
+pub use crate::sample::{sample_point, sample_points};
+pub use crate::sort_key::{from_sort_key, sort_key_prefix, to_sort_key, CompositeKey};
+pub use crate::spacetime::{KeyLayout, SpaceTimeKey, TimeRange};
+pub use crate::tiles::{hash_to_tile, hash_to_tiles, tile_to_hashes, Tile, MERCATOR_MAX_LAT};
+pub use crate::track::{decode_track, encode_track};
+pub use crate::trie::GeohashTrie;
+pub use crate::utm::{decode_mgrs, encode_mgrs, hash_to_mgrs, hash_to_utm, mgrs_to_hashes, Utm};
 pub use geo_types::{Coord, Rect};
@@ -0,0 +1,168 @@
+use crate::core::{encode_int, error_with_precision, grid_to_int, int_to_grid, int_to_hash};
+use crate::geometry::in_range;
+use crate::{Coord, GeohashError};
+use alloc::string::String;
+use alloc::vec::Vec;
+use geo_types::LineString;
+
+/// A geohash cell crossed by a line, with the positions where the line enters and leaves it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineCell {
+    pub hash: String,
+    pub entry: Coord<f64>,
+    pub exit: Coord<f64>,
+}
+
+/// Find the geohashes of length `len` a line passes through, in order along the line.
+///
+/// The cells are found by walking the geohash grid from cell to cell along each segment, in
+/// the style of the Amanatides-Woo traversal, so consecutive cells always share an edge,
+/// however far apart the points of the line are. When the line passes exactly through a corner
+/// of the grid, it moves to the next column first, so the cell there is listed too, with the
+/// corner as its entry and exit, although the line only touches it. A cell is listed again
+/// every time the line comes back to it.
+///
+/// Segments take the shortest way around the world, so a segment between longitudes more than
+/// 180 degrees apart crosses the antimeridian, where the line leaves a cell at a longitude of
+/// 180 and enters the next one at -180.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::cover_line;
+/// use geo_types::line_string;
+///
+/// let line = line_string![(x: -0.5, y: 51.5), (x: 0.5, y: 51.5)];
+/// let cells = cover_line(&line, 2).unwrap();
+///
+/// let hashes: Vec<_> = cells.iter().map(|cell| cell.hash.as_str()).collect();
+/// assert_eq!(hashes, ["gc", "u1"]);
+/// assert_eq!(cells[0].exit, geohash::Coord { x: 0.0, y: 51.5 });
+/// ```
+pub fn cover_line(line: &LineString<f64>, len: usize) -> Result<Vec<LineCell>, GeohashError> {
+    if !(1..=12).contains(&len) {
+        return Err(GeohashError::InvalidLength(len));
+    }
+    if let Some(c) = line.coords().find(|c| !in_range(**c)) {
+        return Err(GeohashError::InvalidCoordinateRange(*c));
+    }
+
+    let bits = len as u32 * 5;
+    let mut cells: Vec<LineCell> = Vec::new();
+    let mut push = |hash: String, entry: Coord<f64>, exit: Coord<f64>| match cells.last_mut() {
+        // segments start in the cell where the previous one ended
+        Some(last) if last.hash == hash => last.exit = exit,
+        _ => cells.push(LineCell { hash, entry, exit }),
+    };
+    if let Some(&first) = line.coords().next() {
+        push(int_to_hash(encode_int(first, bits)?, len), first, first);
+    }
+    for segment in line.lines() {
+        traverse(segment.start, segment.end, bits, |row, col, entry, exit| {
+            push(int_to_hash(grid_to_int(row, col, bits), len), entry, exit)
+        })?;
+    }
+    Ok(cells)
+}
+
+// walks the cells of the grid of `bits` bit hashes from `a` to `b`, calling `visit` with the
+// row and column of each cell and the positions where the segment enters and leaves it
+fn traverse<F: FnMut(u32, u32, Coord<f64>, Coord<f64>)>(
+    a: Coord<f64>,
+    b: Coord<f64>,
+    bits: u32,
+    mut visit: F,
+) -> Result<(), GeohashError> {
+    let (height, width) = error_with_precision(bits);
+    let cols = (360.0 / width) as i64;
+
+    // takes the shortest way around the world, `b.x + shift` may then be out of range
+    let shift = if b.x - a.x > 180.0 {
+        -360.0
+    } else if a.x - b.x > 180.0 {
+        360.0
+    } else {
+        0.0
+    };
+    let (dx, dy) = (b.x + shift - a.x, b.y - a.y);
+
+    // the end points use the same cells as `encode_int`, the columns of `b` are unwrapped
+    let (row, col) = int_to_grid(encode_int(a, bits)?, bits);
+    let (end_row, end_col) = int_to_grid(encode_int(b, bits)?, bits);
+    let (mut row, mut col) = (row as i64, col as i64);
+    let end_row = end_row as i64;
+    let end_col = end_col as i64 + (shift / 360.0) as i64 * cols;
+
+    let step_x = if end_col > col { 1 } else { -1 };
+    let step_y = if end_row > row { 1 } else { -1 };
+    // the next column or row boundary, and the fraction of the segment at which it is crossed,
+    // never along an axis the segment doesn't move on, such as from 180 to -180
+    let crossing = |edge: f64, start: f64, delta: f64| {
+        if delta == 0.0 {
+            f64::INFINITY
+        } else {
+            ((edge - start) / delta).clamp(0.0, 1.0)
+        }
+    };
+    let next_x = |col: i64| {
+        let edge = (col + (step_x > 0) as i64) as f64 * width - 180.0;
+        (edge, crossing(edge, a.x, dx))
+    };
+    let next_y = |row: i64| {
+        let edge = (row + (step_y > 0) as i64) as f64 * height - 90.0;
+        (edge, crossing(edge, a.y, dy))
+    };
+
+    let mut entry = a;
+    loop {
+        let (done_x, done_y) = (col == end_col, row == end_row);
+        if done_x && done_y {
+            break;
+        }
+        // always finish in the cell of `b`, even when rounding disagrees near a boundary
+        let move_x = done_y || (!done_x && next_x(col).1 <= next_y(row).1);
+        // a boundary that is never crossed is only reached at the end of the segment
+        let exit = if move_x {
+            let (x, t) = next_x(col);
+            Coord {
+                x,
+                y: a.y + t.min(1.0) * dy,
+            }
+        } else {
+            let (y, t) = next_y(row);
+            Coord {
+                x: a.x + t.min(1.0) * dx,
+                y,
+            }
+        };
+        let turns = col.div_euclid(cols);
+        visit(
+            row as u32,
+            col.rem_euclid(cols) as u32,
+            wrap(entry, turns),
+            wrap(exit, turns),
+        );
+        entry = exit;
+        if move_x {
+            col += step_x;
+        } else {
+            row += step_y;
+        }
+    }
+    let turns = col.div_euclid(cols);
+    visit(
+        row as u32,
+        col.rem_euclid(cols) as u32,
+        wrap(entry, turns),
+        b,
+    );
+    Ok(())
+}
+
+// moves a position of a column `turns` times around the world back into the valid range
+fn wrap(c: Coord<f64>, turns: i64) -> Coord<f64> {
+    Coord {
+        x: c.x - turns as f64 * 360.0,
+        y: c.y,
+    }
+}
@@ -0,0 +1,125 @@
+use crate::geometry::in_range;
+use crate::{cover_rect, Coord, Format, GeohashError, Rect};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use libm::floor;
+
+// the number of divisions of each pair of characters, from fields down to extended squares
+const DIVISIONS: [u32; 4] = [18, 10, 24, 10];
+// the first character of each pair
+const FIRST_CHARS: [u8; 4] = [b'A', b'0', b'a', b'0'];
+// the number of extended squares across the whole world, in each direction
+const GRID_SIZE: u32 = 18 * 10 * 24 * 10;
+
+const LNG_EXTENT: f64 = 360.0;
+const LAT_EXTENT: f64 = 180.0;
+
+fn invalid(locator: &str) -> GeohashError {
+    GeohashError::InvalidFormat {
+        format: Format::Locator,
+        reason: locator.into(),
+    }
+}
+
+/// Encode a coordinate to a Maidenhead locator of 2, 4, 6 or 8 characters.
+///
+/// Fields are written in upper case and subsquares in lower case, as is customary.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{encode_maidenhead, Coord};
+///
+/// let c = Coord { x: -72.727, y: 41.714 };
+/// assert_eq!(encode_maidenhead(c, 6).unwrap(), "FN31pr");
+/// assert_eq!(encode_maidenhead(c, 2).unwrap(), "FN");
+/// ```
+pub fn encode_maidenhead(c: Coord<f64>, len: usize) -> Result<String, GeohashError> {
+    if !in_range(c) {
+        return Err(GeohashError::InvalidCoordinateRange(c));
+    }
+    if !(2..=8).contains(&len) || len % 2 == 1 {
+        return Err(GeohashError::InvalidFormat {
+            format: Format::Locator,
+            reason: format!("invalid locator length: {}", len),
+        });
+    }
+
+    // the extended square of the coordinate, keeping the north and east edges in the last one
+    let to_grid =
+        |v: f64, extent: f64| (floor(v / extent * GRID_SIZE as f64) as u32).min(GRID_SIZE - 1);
+    let lng = to_grid(c.x + 180.0, LNG_EXTENT);
+    let lat = to_grid(c.y + 90.0, LAT_EXTENT);
+
+    let mut locator = String::with_capacity(len);
+    let mut place = GRID_SIZE;
+    for (&division, &first) in DIVISIONS.iter().zip(&FIRST_CHARS).take(len / 2) {
+        place /= division;
+        locator.push((first + (lng / place % division) as u8) as char);
+        locator.push((first + (lat / place % division) as u8) as char);
+    }
+    Ok(locator)
+}
+
+/// Decode a Maidenhead locator of 2, 4, 6 or 8 characters into the square it describes.
+///
+/// The square is returned as a rectangle, like [`decode_bbox`](crate::decode_bbox) does for
+/// geohashes. Letters are accepted in either case.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{decode_maidenhead, Coord, Rect};
+///
+/// let square = decode_maidenhead("JN18").unwrap();
+/// assert_eq!(square, Rect::new(Coord { x: 2.0, y: 48.0 }, Coord { x: 4.0, y: 49.0 }));
+/// ```
+pub fn decode_maidenhead(locator: &str) -> Result<Rect<f64>, GeohashError> {
+    let bytes = locator.as_bytes();
+    if !(2..=8).contains(&bytes.len()) || bytes.len() % 2 == 1 {
+        return Err(invalid(locator));
+    }
+
+    let (mut lng, mut lat) = (0, 0);
+    let mut place = GRID_SIZE;
+    for (i, pair) in bytes.chunks(2).enumerate() {
+        place /= DIVISIONS[i];
+        for (&b, value) in pair.iter().zip([&mut lng, &mut lat]) {
+            let digit = match FIRST_CHARS[i] {
+                b'0' => b.wrapping_sub(b'0'),
+                first => b
+                    .to_ascii_lowercase()
+                    .wrapping_sub(first.to_ascii_lowercase()),
+            } as u32;
+            if digit >= DIVISIONS[i] {
+                return Err(invalid(locator));
+            }
+            *value += digit * place;
+        }
+    }
+
+    let to_degrees = |v: u32, extent: f64| v as f64 / GRID_SIZE as f64 * extent;
+    Ok(Rect::new(
+        Coord {
+            x: to_degrees(lng, LNG_EXTENT) - 180.0,
+            y: to_degrees(lat, LAT_EXTENT) - 90.0,
+        },
+        Coord {
+            x: to_degrees(lng + place, LNG_EXTENT) - 180.0,
+            y: to_degrees(lat + place, LAT_EXTENT) - 90.0,
+        },
+    ))
+}
+
+/// Find the geohashes of length `len` covering the square of a Maidenhead locator.
+///
+/// ### Examples
+///
+/// ```rust
+/// let hashes = geohash::maidenhead_to_hashes("JN18", 2).unwrap();
+/// assert_eq!(hashes, ["u0"]);
+/// ```
+pub fn maidenhead_to_hashes(locator: &str, len: usize) -> Result<Vec<String>, GeohashError> {
+    cover_rect(decode_maidenhead(locator)?, len)
+}
@@ -1,4 +1,7 @@
+use crate::core::{grid_bits, grid_to_int, hash_to_int, int_to_grid, int_to_hash};
+use crate::GeohashError;
 use alloc::string::String;
+use alloc::vec::Vec;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Neighbors {
@@ -46,3 +49,28 @@ impl Direction {
         }
     }
 }
+
+// a cell and the cells around it, ordered by geohash. Columns wrap around the antimeridian,
+// while rows past the poles are left out, so cells along the poles have 6 cells around them
+pub(crate) fn block(hash: &str) -> Result<Vec<String>, GeohashError> {
+    let bits = hash.len() as u32 * 5;
+    let (lat_bits, lon_bits) = grid_bits(bits);
+    let (rows, cols) = (1i64 << lat_bits, 1i64 << lon_bits);
+    let (lat_idx, lon_idx) = int_to_grid(hash_to_int(hash)?, bits);
+    let mut cells = Vec::with_capacity(9);
+    for lat in lat_idx as i64 - 1..=lat_idx as i64 + 1 {
+        if !(0..rows).contains(&lat) {
+            continue;
+        }
+        for dlon in -1..=1 {
+            let lon = (lon_idx as i64 + dlon).rem_euclid(cols);
+            cells.push(grid_to_int(lat as u32, lon as u32, bits));
+        }
+    }
+    // there are at least 8 columns, so the cells are distinct
+    cells.sort_unstable();
+    Ok(cells
+        .into_iter()
+        .map(|cell| int_to_hash(cell, hash.len()))
+        .collect())
+}
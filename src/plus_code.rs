@@ -0,0 +1,390 @@
+use crate::geometry::in_range;
+use crate::{cover_rect, decode_bbox, Coord, Format, GeohashError, Rect};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use libm::{floor, pow, round};
+
+// the alphabet of Open Location Codes, in order of value
+const ALPHABET: &[u8; 20] = b"23456789CFGHJMPQRVWX";
+const SEPARATOR: char = '+';
+const SEPARATOR_POSITION: usize = 8;
+const PADDING: char = '0';
+
+const ENCODING_BASE: u64 = 20;
+// the number of digits encoded as pairs of latitude and longitude digits
+const PAIR_CODE_LENGTH: usize = 10;
+const MAX_CODE_LENGTH: usize = 15;
+// digits after the pairs divide the area in a grid of 5 rows and 4 columns
+const GRID_ROWS: u64 = 5;
+const GRID_COLUMNS: u64 = 4;
+
+// the place value of the first pair digit, in units of the last pair digit
+const PAIR_FIRST_PLACE_VALUE: u64 = 160_000;
+// the inverse of the size of the area of the last pair digit, in degrees
+const PAIR_PRECISION: u64 = 8000;
+// the inverse of the size of the area of the last grid digit, in degrees
+const FINAL_LAT_PRECISION: u64 = PAIR_PRECISION * 3125;
+const FINAL_LNG_PRECISION: u64 = PAIR_PRECISION * 1024;
+
+// codes can only be shortened if they are at least this long
+const MIN_TRIMMABLE_CODE_LENGTH: usize = 6;
+// the size of the area of each pair, in degrees
+const PAIR_RESOLUTIONS: [f64; 5] = [20.0, 1.0, 0.05, 0.0025, 0.000125];
+
+fn invalid(code: &str) -> GeohashError {
+    GeohashError::InvalidFormat {
+        format: Format::PlusCode,
+        reason: code.into(),
+    }
+}
+
+fn digit_value(c: char) -> Option<u64> {
+    let upper = c.to_ascii_uppercase() as u8;
+    ALPHABET.iter().position(|&a| a == upper).map(|v| v as u64)
+}
+
+/// Encode a coordinate to an Open Location Code ("Plus Code") of `code_length` digits.
+///
+/// Code lengths below 10 must be even, and lengths above 15 are truncated to 15. Codes shorter
+/// than 8 digits are padded with zeros up to the `+` separator.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{encode_plus_code, Coord};
+///
+/// let c = Coord { x: 2.7821875, y: 20.3700625 };
+/// assert_eq!(encode_plus_code(c, 10).unwrap(), "7FG49QCJ+2V");
+/// assert_eq!(encode_plus_code(c, 4).unwrap(), "7FG40000+");
+/// ```
+pub fn encode_plus_code(c: Coord<f64>, code_length: usize) -> Result<String, GeohashError> {
+    if !in_range(c) {
+        return Err(GeohashError::InvalidCoordinateRange(c));
+    }
+    if code_length < 2 || (code_length < PAIR_CODE_LENGTH && code_length % 2 == 1) {
+        return Err(GeohashError::InvalidFormat {
+            format: Format::PlusCode,
+            reason: format!("invalid code length: {}", code_length),
+        });
+    }
+    let code_length = code_length.min(MAX_CODE_LENGTH);
+
+    let lng = if c.x >= 180.0 { c.x - 360.0 } else { c.x };
+    let mut lat = c.y;
+    if lat >= 90.0 {
+        lat -= lat_precision(code_length);
+    }
+
+    // work in integers, rounding away floating point noise first
+    let to_int = |v: f64| floor(round(v * 1e6) / 1e6) as u64;
+    let mut lat_val = to_int((lat + 90.0) * FINAL_LAT_PRECISION as f64);
+    let mut lng_val = to_int((lng + 180.0) * FINAL_LNG_PRECISION as f64);
+
+    let mut digits = Vec::with_capacity(MAX_CODE_LENGTH);
+    if code_length > PAIR_CODE_LENGTH {
+        for _ in PAIR_CODE_LENGTH..MAX_CODE_LENGTH {
+            let index = (lat_val % GRID_ROWS) * GRID_COLUMNS + lng_val % GRID_COLUMNS;
+            digits.push(ALPHABET[index as usize]);
+            lat_val /= GRID_ROWS;
+            lng_val /= GRID_COLUMNS;
+        }
+    } else {
+        lat_val /= FINAL_LAT_PRECISION / PAIR_PRECISION;
+        lng_val /= FINAL_LNG_PRECISION / PAIR_PRECISION;
+    }
+    for _ in 0..PAIR_CODE_LENGTH / 2 {
+        digits.push(ALPHABET[(lng_val % ENCODING_BASE) as usize]);
+        digits.push(ALPHABET[(lat_val % ENCODING_BASE) as usize]);
+        lat_val /= ENCODING_BASE;
+        lng_val /= ENCODING_BASE;
+    }
+    digits.reverse();
+    digits.truncate(code_length);
+
+    let mut code = String::with_capacity(MAX_CODE_LENGTH + 1);
+    for (i, &d) in digits.iter().enumerate() {
+        if i == SEPARATOR_POSITION {
+            code.push(SEPARATOR);
+        }
+        code.push(d as char);
+    }
+    while code.len() < SEPARATOR_POSITION {
+        code.push(PADDING);
+    }
+    if code.len() == SEPARATOR_POSITION {
+        code.push(SEPARATOR);
+    }
+    Ok(code)
+}
+
+// the height in degrees of the area of a code of the given length
+fn lat_precision(code_length: usize) -> f64 {
+    if code_length <= PAIR_CODE_LENGTH {
+        pow(ENCODING_BASE as f64, 2.0 - (code_length / 2) as f64)
+    } else {
+        pow(ENCODING_BASE as f64, -3.0)
+            / pow(GRID_ROWS as f64, (code_length - PAIR_CODE_LENGTH) as f64)
+    }
+}
+
+/// Whether the string is a valid full or short Open Location Code.
+pub fn is_valid_plus_code(code: &str) -> bool {
+    if code.len() < 2 || !code.is_ascii() {
+        return false;
+    }
+    let separator = match code.find(SEPARATOR) {
+        Some(i) if code.matches(SEPARATOR).count() == 1 => i,
+        _ => return false,
+    };
+    if separator > SEPARATOR_POSITION || separator % 2 == 1 {
+        return false;
+    }
+    if let Some(padding) = code.find(PADDING) {
+        // padding is only allowed in full codes, must start on a pair, runs up to the
+        // separator, and ends the code
+        if separator < SEPARATOR_POSITION || padding == 0 || padding % 2 == 1 {
+            return false;
+        }
+        if code[padding..separator].chars().any(|c| c != PADDING) || !code.ends_with(SEPARATOR) {
+            return false;
+        }
+    }
+    if code.len() - separator - 1 == 1 {
+        return false;
+    }
+    code.chars()
+        .all(|c| c == SEPARATOR || c == PADDING || digit_value(c).is_some())
+}
+
+/// Whether the string is a valid short Open Location Code, which can only be decoded relative
+/// to a reference location.
+pub fn is_short_plus_code(code: &str) -> bool {
+    is_valid_plus_code(code) && code.find(SEPARATOR).is_some_and(|i| i < SEPARATOR_POSITION)
+}
+
+/// Whether the string is a valid full Open Location Code.
+pub fn is_full_plus_code(code: &str) -> bool {
+    if !is_valid_plus_code(code) || is_short_plus_code(code) {
+        return false;
+    }
+    let mut chars = code.chars();
+    let first_lat = chars.next().and_then(digit_value).unwrap_or(0) * ENCODING_BASE;
+    let first_lng = chars.next().and_then(digit_value).unwrap_or(0) * ENCODING_BASE;
+    first_lat < 180 && first_lng < 360
+}
+
+/// Decode a full Open Location Code into the area it describes.
+///
+/// The area is returned as a rectangle, like [`decode_bbox`] does for geohashes.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{decode_plus_code, Coord, Rect};
+///
+/// let area = decode_plus_code("7FG49QCJ+2V").unwrap();
+/// let center = area.center();
+/// assert!((center.x - 2.7821875).abs() < 1e-10);
+/// assert!((center.y - 20.3700625).abs() < 1e-10);
+/// ```
+pub fn decode_plus_code(code: &str) -> Result<Rect<f64>, GeohashError> {
+    if !is_full_plus_code(code) {
+        return Err(invalid(code));
+    }
+    let digits: Vec<u64> = code
+        .chars()
+        .filter_map(digit_value)
+        .take(MAX_CODE_LENGTH)
+        .collect();
+
+    let mut lat = 0;
+    let mut lng = 0;
+    let pairs = digits.len().min(PAIR_CODE_LENGTH);
+    let mut place = PAIR_FIRST_PLACE_VALUE;
+    for i in (0..pairs).step_by(2) {
+        lat += digits[i] * place;
+        lng += digits[i + 1] * place;
+        if i < pairs - 2 {
+            place /= ENCODING_BASE;
+        }
+    }
+    let mut lat_size = place as f64 / PAIR_PRECISION as f64;
+    let mut lng_size = place as f64 / PAIR_PRECISION as f64;
+
+    let (mut grid_lat, mut grid_lng) = (0, 0);
+    if digits.len() > PAIR_CODE_LENGTH {
+        let mut row_place = GRID_ROWS.pow(4);
+        let mut col_place = GRID_COLUMNS.pow(4);
+        for (i, &d) in digits.iter().enumerate().skip(PAIR_CODE_LENGTH) {
+            grid_lat += d / GRID_COLUMNS * row_place;
+            grid_lng += d % GRID_COLUMNS * col_place;
+            if i < digits.len() - 1 {
+                row_place /= GRID_ROWS;
+                col_place /= GRID_COLUMNS;
+            }
+        }
+        lat_size = row_place as f64 / FINAL_LAT_PRECISION as f64;
+        lng_size = col_place as f64 / FINAL_LNG_PRECISION as f64;
+    }
+
+    let min = Coord {
+        x: lng as f64 / PAIR_PRECISION as f64 + grid_lng as f64 / FINAL_LNG_PRECISION as f64
+            - 180.0,
+        y: lat as f64 / PAIR_PRECISION as f64 + grid_lat as f64 / FINAL_LAT_PRECISION as f64 - 90.0,
+    };
+    Ok(Rect::new(
+        min,
+        Coord {
+            x: min.x + lng_size,
+            y: min.y + lat_size,
+        },
+    ))
+}
+
+// the center of a decoded area, kept inside the valid ranges
+fn area_center(area: Rect<f64>) -> Coord<f64> {
+    let center = area.center();
+    Coord {
+        x: center.x.min(180.0),
+        y: center.y.min(90.0),
+    }
+}
+
+// the number of digits in a full code
+fn code_length(code: &str) -> usize {
+    code.chars()
+        .filter(|&c| digit_value(c).is_some())
+        .count()
+        .min(MAX_CODE_LENGTH)
+}
+
+/// Shorten a full Open Location Code by removing as many leading digits as can be recovered
+/// from the reference location.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{shorten_plus_code, Coord};
+///
+/// let reference = Coord { x: -1.217765625, y: 51.3701125 };
+/// assert_eq!(shorten_plus_code("9C3W9QCJ+2VX", reference).unwrap(), "+2VX");
+///
+/// let reference = Coord { x: -1.2, y: 51.4 };
+/// assert_eq!(shorten_plus_code("9C3W9QCJ+2VX", reference).unwrap(), "9QCJ+2VX");
+/// ```
+pub fn shorten_plus_code(code: &str, reference: Coord<f64>) -> Result<String, GeohashError> {
+    if !is_full_plus_code(code) || code.contains(PADDING) {
+        return Err(invalid(code));
+    }
+    if !in_range(reference) {
+        return Err(GeohashError::InvalidCoordinateRange(reference));
+    }
+    let area = decode_plus_code(code)?;
+    if code_length(code) < MIN_TRIMMABLE_CODE_LENGTH {
+        return Err(invalid(code));
+    }
+
+    let center = area_center(area);
+    let range = (center.y - reference.y)
+        .abs()
+        .max((center.x - reference.x).abs());
+    let code = code.to_ascii_uppercase();
+    for i in (1..PAIR_RESOLUTIONS.len() - 1).rev() {
+        // keep a safety margin of 30% of the area size from the edge of the recovery area
+        if range < PAIR_RESOLUTIONS[i] * 0.3 {
+            return Ok(code[(i + 1) * 2..].into());
+        }
+    }
+    Ok(code)
+}
+
+/// Recover a full Open Location Code from a short code, using the full code nearest to the
+/// reference location.
+///
+/// Full codes are returned as they are.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{recover_plus_code, Coord};
+///
+/// let reference = Coord { x: -1.2, y: 51.4 };
+/// assert_eq!(recover_plus_code("9QCJ+2VX", reference).unwrap(), "9C3W9QCJ+2VX");
+/// ```
+pub fn recover_plus_code(code: &str, reference: Coord<f64>) -> Result<String, GeohashError> {
+    if !is_short_plus_code(code) {
+        if is_full_plus_code(code) {
+            return Ok(code.to_ascii_uppercase());
+        }
+        return Err(invalid(code));
+    }
+    if !in_range(reference) {
+        return Err(GeohashError::InvalidCoordinateRange(reference));
+    }
+
+    let padding_length = SEPARATOR_POSITION - code.find(SEPARATOR).unwrap_or(0);
+    let resolution = pow(ENCODING_BASE as f64, 2.0 - (padding_length / 2) as f64);
+    let half_resolution = resolution / 2.0;
+
+    let prefix = encode_plus_code(reference, PAIR_CODE_LENGTH)?;
+    let full = format!("{}{}", &prefix[..padding_length], code.to_ascii_uppercase());
+    let area = decode_plus_code(&full)?;
+    let mut center = area_center(area);
+
+    // the nearest match may be in a neighboring area of the prefix
+    if reference.y + half_resolution < center.y && center.y - resolution >= -90.0 {
+        center.y -= resolution;
+    } else if reference.y - half_resolution > center.y && center.y + resolution <= 90.0 {
+        center.y += resolution;
+    }
+    if reference.x + half_resolution < center.x {
+        center.x -= resolution;
+    } else if reference.x - half_resolution > center.x {
+        center.x += resolution;
+    }
+    if center.x < -180.0 {
+        center.x += 360.0;
+    } else if center.x >= 180.0 {
+        center.x -= 360.0;
+    }
+    encode_plus_code(center, code_length(&full))
+}
+
+/// Find the geohashes of length `len` covering the area of a full Open Location Code.
+///
+/// ### Examples
+///
+/// ```rust
+/// let hashes = geohash::plus_code_to_hashes("9C3W9Q00+", 5).unwrap();
+/// assert_eq!(hashes, ["gcph5", "gcph7", "gcphh", "gcphk"]);
+/// ```
+pub fn plus_code_to_hashes(code: &str, len: usize) -> Result<Vec<String>, GeohashError> {
+    cover_rect(decode_plus_code(code)?, len)
+}
+
+/// Find the longest Open Location Code whose area contains a whole geohash, or `None` if the
+/// geohash is larger than any code area.
+///
+/// ### Examples
+///
+/// ```rust
+/// let code = geohash::hash_to_plus_code("gcph5xy").unwrap();
+/// assert_eq!(code, Some("9C3W9QC4+".to_owned()));
+/// ```
+pub fn hash_to_plus_code(hash_str: &str) -> Result<Option<String>, GeohashError> {
+    let cell = decode_bbox(hash_str)?;
+    let (min, max) = (cell.min(), cell.max());
+    for code_length in [15, 14, 13, 12, 11, 10, 8, 6, 4, 2] {
+        let code = encode_plus_code(cell.center(), code_length)?;
+        let area = decode_plus_code(&code)?;
+        if area.min().x <= min.x
+            && area.min().y <= min.y
+            && max.x <= area.max().x
+            && max.y <= area.max().y
+        {
+            return Ok(Some(code));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,242 @@
+use crate::core::{bbox_int_with_precision, error_with_precision, grid_to_int, int_to_hash};
+use crate::{GeohashError, Rect};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Range, RangeInclusive};
+use libm::{ceil, floor};
+
+/// Decompose a rectangle into ranges of integer geohashes of `bits` bits.
+///
+/// Every coordinate inside `rect` encodes (with [`encode_int`](crate::encode_int)) to a hash
+/// inside one of the returned `[lo, hi]` ranges, which makes them suitable for range scans
+/// over a sorted store keyed by integer geohash. The ranges are sorted and never overlap or
+/// touch.
+///
+/// The ends of the ranges are inclusive rather than exclusive: with all 64 bits, the range of
+/// the last cell ends at `u64::MAX`, the hash of the north east corner of the world, and an
+/// exclusive end past it would not fit in a `u64`.
+///
+/// At most `max_ranges` ranges are returned. The rectangle is refined one bit at a time until
+/// covering it more tightly would need more ranges than that, and the smallest gaps between
+/// the ranges of that last refinement are then filled in. A larger `max_ranges` means fewer
+/// false positives in exchange for more range scans.
+///
+/// Rectangles are not wrapped around the antimeridian, so a query crossing it has to be split
+/// into two rectangles first.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{encode_int, rect_to_ranges, Coord, Rect};
+///
+/// let rect = Rect::new(Coord { x: -122.5, y: 37.7 }, Coord { x: -122.3, y: 37.8 });
+/// let ranges = rect_to_ranges(rect, 40, 8).unwrap();
+/// assert!(ranges.len() <= 8);
+///
+/// let key = encode_int(Coord { x: -122.4, y: 37.75 }, 40).unwrap();
+/// assert!(ranges.iter().any(|r| r.contains(&key)));
+/// ```
+pub fn rect_to_ranges(
+    rect: Rect<f64>,
+    bits: u32,
+    max_ranges: usize,
+) -> Result<Vec<RangeInclusive<u64>>, GeohashError> {
+    refine_ranges(rect, bits, max_ranges, bbox_int_with_precision)
+}
+
+/// The refinement behind [`rect_to_ranges`], for any space filling curve whose keys of `bits`
+/// bits are described by `bbox`, giving the bounding box of every `depth` bit prefix.
+pub(crate) fn refine_ranges<F: Fn(u64, u32) -> Rect<f64>>(
+    rect: Rect<f64>,
+    bits: u32,
+    max_ranges: usize,
+    bbox: F,
+) -> Result<Vec<RangeInclusive<u64>>, GeohashError> {
+    if !(1..=64).contains(&bits) {
+        return Err(GeohashError::InvalidBits(bits));
+    }
+    for c in [rect.min(), rect.max()] {
+        if !(-180.0..=180.0).contains(&c.x) || !(-90.0..=90.0).contains(&c.y) {
+            return Err(GeohashError::InvalidCoordinateRange(c));
+        }
+    }
+    Ok(refine(bits, max_ranges, |prefix, depth| {
+        classify(bbox(prefix, depth), rect)
+    }))
+}
+
+/// Refines the ranges of keys of `bits` bits one bit at a time, where `coverage` tells how
+/// much of the query the keys starting with every `depth` bit prefix cover.
+pub(crate) fn refine<F: Fn(u64, u32) -> Coverage>(
+    bits: u32,
+    max_ranges: usize,
+    coverage: F,
+) -> Vec<RangeInclusive<u64>> {
+    let max_ranges = max_ranges.max(1);
+
+    let mut ranges = Vec::new();
+    // cells that are only partially covered by the query at the current depth
+    let mut partial = Vec::from([0u64]);
+    let mut full = Vec::new();
+    for depth in 1..=bits {
+        let mut next_partial = Vec::new();
+        let mut next_full = full.clone();
+        for child in partial.iter().flat_map(|&cell| [cell << 1, cell << 1 | 1]) {
+            match coverage(child, depth) {
+                Coverage::Outside => {}
+                Coverage::Inside => next_full.push(to_range(child, depth, bits)),
+                Coverage::Partial => next_partial.push(child),
+            }
+        }
+        let mut candidates = next_full.clone();
+        candidates.extend(next_partial.iter().map(|&c| to_range(c, depth, bits)));
+        let candidates = merge(candidates);
+        if candidates.len() > max_ranges {
+            ranges = fill_gaps(candidates, max_ranges);
+            break;
+        }
+        ranges = candidates;
+        partial = next_partial;
+        full = next_full;
+        if partial.is_empty() {
+            break;
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|r| r.start as u64..=(r.end - 1) as u64)
+        .collect()
+}
+
+/// The largest number of geohashes returned by [`cover_rect`].
+pub const MAX_COVER_CELLS: usize = 1 << 20;
+
+/// Find the geohashes of length `len` covering a rectangle.
+///
+/// These are the cells overlapping the inside of the rectangle, so cells that only touch one
+/// of its edges are left out, unless the rectangle has no area at all. The geohashes are
+/// returned in Z-order.
+///
+/// A rectangle needing more than [`MAX_COVER_CELLS`] geohashes is an error, rather than an
+/// allocation of billions of strings for large rectangles and long geohashes. Use
+/// [`rect_to_ranges`] to cover those.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{cover_rect, decode_bbox, Coord, Rect};
+///
+/// let rect = Rect::new(Coord { x: -0.2, y: 51.4 }, Coord { x: 0.1, y: 51.6 });
+/// assert_eq!(cover_rect(rect, 3).unwrap(), ["gcp", "u10"]);
+///
+/// // a geohash is covered by itself
+/// assert_eq!(cover_rect(decode_bbox("u10").unwrap(), 3).unwrap(), ["u10"]);
+/// ```
+pub fn cover_rect(rect: Rect<f64>, len: usize) -> Result<Vec<String>, GeohashError> {
+    if !(1..=12).contains(&len) {
+        return Err(GeohashError::InvalidLength(len));
+    }
+    let (min, max) = (rect.min(), rect.max());
+    for c in [min, max] {
+        if !(-180.0..=180.0).contains(&c.x) || !(-90.0..=90.0).contains(&c.y) {
+            return Err(GeohashError::InvalidCoordinateRange(c));
+        }
+    }
+
+    let bits = len as u32 * 5;
+    let (height, width) = error_with_precision(bits);
+    let rows = cell_span(min.y + 90.0, max.y + 90.0, height, 180.0);
+    let cols = cell_span(min.x + 180.0, max.x + 180.0, width, 360.0);
+    let count = (*rows.end() - *rows.start() + 1) as u64 * (*cols.end() - *cols.start() + 1) as u64;
+    if count > MAX_COVER_CELLS as u64 {
+        return Err(GeohashError::TooManyCells {
+            count,
+            max: MAX_COVER_CELLS,
+        });
+    }
+    let mut cells: Vec<u64> = rows
+        .flat_map(|row| cols.clone().map(move |col| grid_to_int(row, col, bits)))
+        .collect();
+    cells.sort_unstable();
+    Ok(cells
+        .into_iter()
+        .map(|cell| int_to_hash(cell, len))
+        .collect())
+}
+
+/// The indices of the cells of size `size` overlapping the inside of `[lo, hi]` on an axis of
+/// length `extent` starting at zero, or the cell containing `lo` if the interval is empty.
+pub(crate) fn cell_span(lo: f64, hi: f64, size: f64, extent: f64) -> RangeInclusive<u32> {
+    let last = (extent / size) as u32 - 1;
+    let first = (floor(lo / size).max(0.0) as u32).min(last);
+    let end = ((ceil(hi / size) - 1.0).max(0.0) as u32).clamp(first, last);
+    first..=end
+}
+
+pub(crate) enum Coverage {
+    Outside,
+    Inside,
+    Partial,
+}
+
+pub(crate) fn classify(bbox: Rect<f64>, rect: Rect<f64>) -> Coverage {
+    let (min, max) = (bbox.min(), bbox.max());
+    let (r_min, r_max) = (rect.min(), rect.max());
+
+    // cells include their south and west edges, and their north and east edges only at the
+    // edge of the world, which is the same rule `encode_int` uses to pick a cell
+    let overlaps_x = min.x <= r_max.x && (r_min.x < max.x || max.x >= 180.0);
+    let overlaps_y = min.y <= r_max.y && (r_min.y < max.y || max.y >= 90.0);
+    if !overlaps_x || !overlaps_y {
+        Coverage::Outside
+    } else if r_min.x <= min.x && max.x <= r_max.x && r_min.y <= min.y && max.y <= r_max.y {
+        Coverage::Inside
+    } else {
+        Coverage::Partial
+    }
+}
+
+// the range of `bits` bit hashes that start with the `depth` bit prefix `cell`, the range of
+// the last cell ends past `u64::MAX` when using all 64 bits
+fn to_range(cell: u64, depth: u32, bits: u32) -> Range<u128> {
+    let shift = bits - depth;
+    (cell as u128) << shift..(cell as u128 + 1) << shift
+}
+
+// sorts the ranges and joins the ones that touch
+pub(crate) fn merge<T: Ord + Copy>(mut ranges: Vec<Range<T>>) -> Vec<Range<T>> {
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+    for r in ranges {
+        match merged.last_mut() {
+            Some(last) if last.end >= r.start => last.end = last.end.max(r.end),
+            _ => merged.push(r),
+        }
+    }
+    merged
+}
+
+// fills the smallest gaps between sorted, disjoint ranges until only `max_ranges` are left
+fn fill_gaps(ranges: Vec<Range<u128>>, max_ranges: usize) -> Vec<Range<u128>> {
+    let mut gaps: Vec<(u128, usize)> = ranges
+        .windows(2)
+        .enumerate()
+        .map(|(i, w)| (w[1].start - w[0].end, i))
+        .collect();
+    gaps.sort();
+    let mut filled = alloc::vec![false; ranges.len()];
+    for &(_, i) in gaps.iter().take(ranges.len() - max_ranges) {
+        filled[i] = true;
+    }
+
+    let mut out: Vec<Range<u128>> = Vec::with_capacity(max_ranges);
+    let mut join = false;
+    for (i, r) in ranges.into_iter().enumerate() {
+        match out.last_mut() {
+            Some(last) if join => last.end = r.end,
+            _ => out.push(r),
+        }
+        join = filled[i];
+    }
+    out
+}
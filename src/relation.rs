@@ -0,0 +1,111 @@
+use crate::core::{grid_bits, hash_to_int, int_to_grid};
+use crate::{Direction, GeohashError};
+
+// the number of latitude and longitude bits of the longest geohashes, cell edges at every
+// length fall on the grid of that precision
+const MAX_BITS: u32 = 30;
+
+// a half-open range of rows or of columns
+type Span = (u64, u64);
+
+/// How two geohash cells are placed relative to each other, as returned by [`relation`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellRelation {
+    /// The cells have no point in common.
+    Disjoint,
+    /// The cells share an edge or a corner, with the second cell in this direction of the
+    /// first one.
+    Touching(Direction),
+    /// The first cell contains the second one.
+    Contains,
+    /// The first cell is within the second one.
+    Within,
+    /// The cells are the same.
+    Equal,
+}
+
+/// The relation between the cells of two geohashes, possibly of different lengths.
+///
+/// Cells either are nested, or their interiors don't overlap, so two cells are compared by
+/// their edges only. This is done exactly on the integer rows and columns of their bounding
+/// boxes, which wrap around the antimeridian: the cells on each side of it are touching. They
+/// don't wrap around the poles.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{relation, CellRelation, Direction};
+///
+/// assert_eq!(relation("u09", "u09tun").unwrap(), CellRelation::Contains);
+/// assert_eq!(relation("u09tun", "u09").unwrap(), CellRelation::Within);
+/// assert_eq!(relation("u09", "u0d").unwrap(), CellRelation::Touching(Direction::E));
+/// assert_eq!(relation("u09", "u0f").unwrap(), CellRelation::Touching(Direction::NE));
+/// assert_eq!(relation("xz", "8p").unwrap(), CellRelation::Touching(Direction::E));
+/// assert_eq!(relation("u09", "u0f5").unwrap(), CellRelation::Disjoint);
+/// ```
+pub fn relation(a: &str, b: &str) -> Result<CellRelation, GeohashError> {
+    let (a_lat, a_lon) = grid_ranges(a)?;
+    let (b_lat, b_lon) = grid_ranges(b)?;
+
+    if a_lat == b_lat && a_lon == b_lon {
+        return Ok(CellRelation::Equal);
+    }
+    if covers(a_lat, b_lat) && covers(a_lon, b_lon) {
+        return Ok(CellRelation::Contains);
+    }
+    if covers(b_lat, a_lat) && covers(b_lon, a_lon) {
+        return Ok(CellRelation::Within);
+    }
+
+    let dlat = if overlaps(a_lat, b_lat) {
+        0
+    } else if b_lat.0 == a_lat.1 {
+        1
+    } else if b_lat.1 == a_lat.0 {
+        -1
+    } else {
+        return Ok(CellRelation::Disjoint);
+    };
+    let full = 1 << MAX_BITS;
+    let dlon = if overlaps(a_lon, b_lon) {
+        0
+    } else if b_lon.0 == a_lon.1 % full {
+        1
+    } else if b_lon.1 % full == a_lon.0 {
+        -1
+    } else {
+        return Ok(CellRelation::Disjoint);
+    };
+
+    Ok(CellRelation::Touching(match (dlat, dlon) {
+        (1, -1) => Direction::NW,
+        (1, 0) => Direction::N,
+        (1, _) => Direction::NE,
+        (0, -1) => Direction::W,
+        (0, _) => Direction::E,
+        (_, -1) => Direction::SW,
+        (_, 0) => Direction::S,
+        _ => Direction::SE,
+    }))
+}
+
+// the half-open ranges of rows and of columns covered by a cell, on the grid of the longest
+// geohashes
+fn grid_ranges(hash_str: &str) -> Result<(Span, Span), GeohashError> {
+    let bits = hash_str.len() as u32 * 5;
+    let (lat_idx, lon_idx) = int_to_grid(hash_to_int(hash_str)?, bits);
+    let (lat_bits, lon_bits) = grid_bits(bits);
+    let range = |idx: u32, bits: u32| {
+        let shift = MAX_BITS - bits;
+        ((idx as u64) << shift, (idx as u64 + 1) << shift)
+    };
+    Ok((range(lat_idx, lat_bits), range(lon_idx, lon_bits)))
+}
+
+fn covers(outer: Span, inner: Span) -> bool {
+    outer.0 <= inner.0 && inner.1 <= outer.1
+}
+
+fn overlaps(a: Span, b: Span) -> bool {
+    a.0 < b.1 && b.0 < a.1
+}
@@ -0,0 +1,386 @@
+use crate::geometry::in_range;
+use crate::{cover_rect, decode_bbox, Coord, Format, GeohashError, Rect};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use libm::{atan2, copysign, cos, floor, sin, sqrt};
+
+// the deepest level of the S2 hierarchy, where cells are about a centimeter across
+const MAX_LEVEL: u8 = 30;
+const MAX_SIZE: u32 = 1 << MAX_LEVEL;
+
+// orientations of the Hilbert curve within a cell: whether i and j are swapped, and whether
+// they are inverted
+const SWAP_MASK: usize = 1;
+const INVERT_MASK: usize = 2;
+// the position along the curve of each child (i << 1 | j), for each orientation
+const IJ_TO_POS: [[u64; 4]; 4] = [[0, 1, 3, 2], [0, 3, 1, 2], [2, 3, 1, 0], [2, 1, 3, 0]];
+// the child (i << 1 | j) at each position along the curve, for each orientation
+const POS_TO_IJ: [[u32; 4]; 4] = [[0, 1, 3, 2], [0, 2, 3, 1], [3, 2, 0, 1], [3, 1, 0, 2]];
+// how the orientation changes when descending into the child at each position
+const POS_TO_ORIENTATION: [usize; 4] = [SWAP_MASK, 0, 0, INVERT_MASK | SWAP_MASK];
+
+// the number of points sampled along each edge of a cell to find its bounding box
+const EDGE_SAMPLES: u32 = 8;
+
+fn invalid_id(id: u64) -> GeohashError {
+    GeohashError::InvalidFormat {
+        format: Format::S2Cell,
+        reason: format!("{:#018x}", id),
+    }
+}
+
+fn check_level(level: u8) -> Result<(), GeohashError> {
+    if level > MAX_LEVEL {
+        return Err(GeohashError::InvalidFormat {
+            format: Format::S2Cell,
+            reason: format!("level {}", level),
+        });
+    }
+    Ok(())
+}
+
+// a cell on one of the six faces of the cube, in leaf cell coordinates
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    face: u8,
+    i: u32,
+    j: u32,
+    level: u8,
+}
+
+impl Cell {
+    fn size(&self) -> u32 {
+        MAX_SIZE >> self.level
+    }
+
+    fn id(&self) -> u64 {
+        let mut orientation = self.face as usize & SWAP_MASK;
+        let mut pos = 0;
+        for k in (MAX_LEVEL - self.level..MAX_LEVEL).rev() {
+            let ij = ((self.i >> k & 1) << 1 | (self.j >> k & 1)) as usize;
+            let p = IJ_TO_POS[orientation][ij];
+            pos = pos << 2 | p;
+            orientation ^= POS_TO_ORIENTATION[p as usize];
+        }
+        let shift = 2 * (MAX_LEVEL - self.level) as u64;
+        (self.face as u64) << 61 | pos << (shift + 1) | 1 << shift
+    }
+
+    fn from_id(id: u64) -> Result<Cell, GeohashError> {
+        let trailing = id.trailing_zeros();
+        if id >> 61 > 5 || trailing > 60 || trailing % 2 == 1 {
+            return Err(invalid_id(id));
+        }
+        let face = (id >> 61) as u8;
+        let level = MAX_LEVEL - trailing as u8 / 2;
+        let pos = id >> (trailing + 1);
+
+        let mut orientation = face as usize & SWAP_MASK;
+        let (mut i, mut j) = (0, 0);
+        for k in (0..level).rev() {
+            let p = (pos >> (2 * k) & 3) as usize;
+            let ij = POS_TO_IJ[orientation][p];
+            i = i << 1 | ij >> 1;
+            j = j << 1 | ij & 1;
+            orientation ^= POS_TO_ORIENTATION[p];
+        }
+        let shift = MAX_LEVEL - level;
+        Ok(Cell {
+            face,
+            i: i << shift,
+            j: j << shift,
+            level,
+        })
+    }
+
+    fn children(&self) -> impl Iterator<Item = Cell> + '_ {
+        let half = self.size() / 2;
+        (0..4).map(move |k| Cell {
+            face: self.face,
+            i: self.i + (k >> 1) * half,
+            j: self.j + (k & 1) * half,
+            level: self.level + 1,
+        })
+    }
+
+    // whether the cell contains the north or south pole, at the centers of faces 2 and 5
+    fn contains_pole(&self) -> bool {
+        let center = MAX_SIZE / 2;
+        (self.face == 2 || self.face == 5)
+            && (self.i..self.i + self.size()).contains(&center)
+            && (self.j..self.j + self.size()).contains(&center)
+    }
+
+    // the bounding box of the cell in latitude and longitude, with longitudes left unwrapped
+    // around the center of the cell so that it may extend past the antimeridian
+    //
+    // The box is found from points sampled along the edges of the cell, and padded to make
+    // up for the edges bulging towards the poles between samples.
+    fn bounds(&self) -> (f64, f64, f64, f64) {
+        let (size, n) = (self.size() as f64, MAX_SIZE as f64);
+        let point = |di: f64, dj: f64| {
+            face_st_to_coord(
+                self.face,
+                (self.i as f64 + di * size) / n,
+                (self.j as f64 + dj * size) / n,
+            )
+        };
+        let center = point(0.5, 0.5);
+        if self.contains_pole() {
+            return if self.face == 2 {
+                (-180.0, 180.0, self.south_edge(), 90.0)
+            } else {
+                (-180.0, 180.0, -90.0, self.north_edge())
+            };
+        }
+
+        let (mut west, mut east) = (center.x, center.x);
+        let (mut south, mut north) = (center.y, center.y);
+        for k in 0..EDGE_SAMPLES {
+            let t = k as f64 / EDGE_SAMPLES as f64;
+            for c in [
+                point(t, 0.0),
+                point(1.0, t),
+                point(1.0 - t, 1.0),
+                point(0.0, 1.0 - t),
+            ] {
+                let mut lon = c.x;
+                if lon - center.x > 180.0 {
+                    lon -= 360.0;
+                } else if center.x - lon > 180.0 {
+                    lon += 360.0;
+                }
+                west = west.min(lon);
+                east = east.max(lon);
+                south = south.min(c.y);
+                north = north.max(c.y);
+            }
+        }
+        let margin = (north - south) / 16.0 + 1e-9;
+        (
+            west - 1e-9,
+            east + 1e-9,
+            (south - margin).max(-90.0),
+            (north + margin).min(90.0),
+        )
+    }
+
+    // the lowest latitude of a cell around the north pole
+    fn south_edge(&self) -> f64 {
+        self.edge_latitude(f64::min)
+    }
+
+    // the highest latitude of a cell around the south pole
+    fn north_edge(&self) -> f64 {
+        self.edge_latitude(f64::max)
+    }
+
+    fn edge_latitude(&self, pick: fn(f64, f64) -> f64) -> f64 {
+        let (size, n) = (self.size() as f64, MAX_SIZE as f64);
+        let mut lat = face_st_to_coord(self.face, self.i as f64 / n, self.j as f64 / n).y;
+        for k in 0..EDGE_SAMPLES {
+            let t = k as f64 / EDGE_SAMPLES as f64;
+            for (di, dj) in [(t, 0.0), (1.0, t), (1.0 - t, 1.0), (0.0, 1.0 - t)] {
+                let c = face_st_to_coord(
+                    self.face,
+                    (self.i as f64 + di * size) / n,
+                    (self.j as f64 + dj * size) / n,
+                );
+                lat = pick(lat, c.y);
+            }
+        }
+        // the edges of a polar cell bulge away from the pole between samples
+        let margin = (90.0 - lat.abs()) / 16.0;
+        copysign((lat.abs() - margin).max(0.0), lat)
+    }
+}
+
+// the quadratic transform from S2, between the gnomonic projection (u, v) of a face and the
+// cell coordinates (s, t) which keeps cells of a level roughly equal in area
+fn uv_to_st(u: f64) -> f64 {
+    if u >= 0.0 {
+        0.5 * sqrt(1.0 + 3.0 * u)
+    } else {
+        1.0 - 0.5 * sqrt(1.0 - 3.0 * u)
+    }
+}
+
+fn st_to_uv(s: f64) -> f64 {
+    if s >= 0.5 {
+        (4.0 * s * s - 1.0) / 3.0
+    } else {
+        (1.0 - 4.0 * (1.0 - s) * (1.0 - s)) / 3.0
+    }
+}
+
+fn face_st_to_coord(face: u8, s: f64, t: f64) -> Coord<f64> {
+    let (u, v) = (st_to_uv(s), st_to_uv(t));
+    let (x, y, z) = match face {
+        0 => (1.0, u, v),
+        1 => (-u, 1.0, v),
+        2 => (-u, -v, 1.0),
+        3 => (-1.0, -v, -u),
+        4 => (v, -1.0, -u),
+        _ => (v, u, -1.0),
+    };
+    Coord {
+        x: atan2(y, x).to_degrees(),
+        y: atan2(z, sqrt(x * x + y * y)).to_degrees(),
+    }
+}
+
+// the leaf cell containing a coordinate
+fn leaf_cell(c: Coord<f64>) -> Cell {
+    let (lat, lon) = (c.y.to_radians(), c.x.to_radians());
+    let (x, y, z) = (cos(lat) * cos(lon), cos(lat) * sin(lon), sin(lat));
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+    let (face, u, v) = if ax >= ay && ax >= az {
+        if x > 0.0 {
+            (0, y / x, z / x)
+        } else {
+            (3, z / x, y / x)
+        }
+    } else if ay >= az {
+        if y > 0.0 {
+            (1, -x / y, z / y)
+        } else {
+            (4, z / y, -x / y)
+        }
+    } else if z > 0.0 {
+        (2, -x / z, -y / z)
+    } else {
+        (5, -y / z, -x / z)
+    };
+    let to_ij = |w: f64| (floor(uv_to_st(w) * MAX_SIZE as f64).max(0.0) as u32).min(MAX_SIZE - 1);
+    Cell {
+        face,
+        i: to_ij(u),
+        j: to_ij(v),
+        level: MAX_LEVEL,
+    }
+}
+
+/// The ID of the S2 cell at `level` containing a coordinate.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{s2_cell_id, Coord};
+///
+/// let id = s2_cell_id(Coord { x: -74.0060, y: 40.7128 }, 8).unwrap();
+/// assert_eq!(id, 0x89c2_5000_0000_0000);
+/// ```
+pub fn s2_cell_id(c: Coord<f64>, level: u8) -> Result<u64, GeohashError> {
+    if !in_range(c) {
+        return Err(GeohashError::InvalidCoordinateRange(c));
+    }
+    check_level(level)?;
+    let leaf = leaf_cell(c);
+    let shift = MAX_LEVEL - level;
+    Ok(Cell {
+        i: leaf.i >> shift << shift,
+        j: leaf.j >> shift << shift,
+        level,
+        ..leaf
+    }
+    .id())
+}
+
+// whether an interval of longitudes, which may extend past the antimeridian, overlaps the
+// longitudes of a rectangle
+fn overlaps_lon(west: f64, east: f64, rect: Rect<f64>) -> bool {
+    [-360.0, 0.0, 360.0]
+        .iter()
+        .any(|shift| overlaps(west, east, rect.min().x + shift, rect.max().x + shift))
+}
+
+// whether two intervals overlap, where a degenerate second interval only needs to touch
+fn overlaps(lo: f64, hi: f64, other_lo: f64, other_hi: f64) -> bool {
+    if other_lo == other_hi {
+        lo <= other_lo && other_lo <= hi
+    } else {
+        lo < other_hi && other_lo < hi
+    }
+}
+
+/// Find the IDs of the S2 cells at `level` covering a rectangle, such as the bounding box of
+/// a geohash, sorted in the order of the S2 Hilbert curve.
+///
+/// Cells are tested against the rectangle using slightly enlarged bounding boxes, so the
+/// covering may include a few cells around the edges of the rectangle that don't actually
+/// overlap it.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{decode_bbox, rect_to_s2_cells};
+///
+/// let cells = rect_to_s2_cells(decode_bbox("dr5rs").unwrap(), 8).unwrap();
+/// assert_eq!(cells, [0x89c2_5000_0000_0000]);
+/// ```
+pub fn rect_to_s2_cells(rect: Rect<f64>, level: u8) -> Result<Vec<u64>, GeohashError> {
+    check_level(level)?;
+    for c in [rect.min(), rect.max()] {
+        if !in_range(c) {
+            return Err(GeohashError::InvalidCoordinateRange(c));
+        }
+    }
+
+    let mut ids = Vec::new();
+    let mut stack: Vec<Cell> = (0..6)
+        .rev()
+        .map(|face| Cell {
+            face,
+            i: 0,
+            j: 0,
+            level: 0,
+        })
+        .collect();
+    while let Some(cell) = stack.pop() {
+        let (west, east, south, north) = cell.bounds();
+        if !overlaps(south, north, rect.min().y, rect.max().y) || !overlaps_lon(west, east, rect) {
+            continue;
+        }
+        if cell.level == level {
+            ids.push(cell.id());
+        } else {
+            stack.extend(cell.children());
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// Find the IDs of the S2 cells at `level` covering a geohash.
+pub fn hash_to_s2_cells(hash_str: &str, level: u8) -> Result<Vec<u64>, GeohashError> {
+    rect_to_s2_cells(decode_bbox(hash_str)?, level)
+}
+
+/// Find the geohashes of length `len` covering an S2 cell.
+///
+/// The geohashes cover the slightly enlarged bounding box of the cell, so a few of them
+/// around its edges may not actually overlap it.
+///
+/// ### Examples
+///
+/// ```
+/// let hashes = geohash::s2_cell_to_hashes(0x89c2_5000_0000_0000, 3).unwrap();
+/// assert_eq!(hashes, ["dr5", "dr7"]);
+/// ```
+pub fn s2_cell_to_hashes(id: u64, len: usize) -> Result<Vec<String>, GeohashError> {
+    let (west, east, south, north) = Cell::from_id(id)?.bounds();
+    let rect =
+        |west: f64, east: f64| Rect::new(Coord { x: west, y: south }, Coord { x: east, y: north });
+    let mut hashes = Vec::new();
+    if west < -180.0 {
+        hashes.extend(cover_rect(rect(west + 360.0, 180.0), len)?);
+    }
+    if east > 180.0 {
+        hashes.extend(cover_rect(rect(-180.0, east - 360.0), len)?);
+    }
+    hashes.extend(cover_rect(rect(west.max(-180.0), east.min(180.0)), len)?);
+    hashes.sort_unstable();
+    hashes.dedup();
+    Ok(hashes)
+}
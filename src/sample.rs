@@ -0,0 +1,85 @@
+use crate::distance::rect_area;
+use crate::{decode_bbox, CellSet, Coord, GeohashError, Rect};
+use alloc::vec::Vec;
+use libm::{asin, sin};
+use rand::Rng;
+
+/// Draw a random point inside the cell of a geohash, uniformly by surface area.
+///
+/// Cells are narrower towards the poles than towards the equator, so the latitude is drawn with
+/// more weight on the side closer to the equator, which makes every part of the cell with the
+/// same area on the earth equally likely. The point is inside the closed cell given by
+/// [`decode_bbox`].
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{decode_bbox, sample_point};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let c = sample_point("u09tun", &mut rng).unwrap();
+///
+/// let rect = decode_bbox("u09tun").unwrap();
+/// assert!(rect.min().x <= c.x && c.x <= rect.max().x);
+/// assert!(rect.min().y <= c.y && c.y <= rect.max().y);
+/// ```
+pub fn sample_point<R: Rng + ?Sized>(
+    hash_str: &str,
+    rng: &mut R,
+) -> Result<Coord<f64>, GeohashError> {
+    Ok(point_in_rect(decode_bbox(hash_str)?, rng))
+}
+
+/// Draw `n` random points inside the cells of a [`CellSet`], uniformly by surface area.
+///
+/// A cell is picked with a probability proportional to its area on the earth, and then a
+/// point inside of it as with [`sample_point`]. There are no points if the set is empty.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{sample_points, CellSet};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let zone = CellSet::from_hashes(["u09", "u0d5"]).unwrap();
+/// let points = sample_points(&zone, 100, &mut StdRng::seed_from_u64(7));
+/// assert_eq!(points.len(), 100);
+/// assert!(points.iter().all(|&c| zone.contains_point(c)));
+/// ```
+pub fn sample_points<R: Rng + ?Sized>(set: &CellSet, n: usize, rng: &mut R) -> Vec<Coord<f64>> {
+    // the cells and the total area up to the end of each of them
+    let mut total = 0.0;
+    let cells: Vec<(Rect<f64>, f64)> = set
+        .rects()
+        .map(|rect| {
+            total += rect_area(rect);
+            (rect, total)
+        })
+        .collect();
+    if cells.is_empty() {
+        return Vec::new();
+    }
+    (0..n)
+        .map(|_| {
+            let at = rng.gen::<f64>() * total;
+            let i = cells
+                .partition_point(|&(_, end)| end <= at)
+                .min(cells.len() - 1);
+            point_in_rect(cells[i].0, rng)
+        })
+        .collect()
+}
+
+// the area of a band of latitudes is proportional to the difference of their sines, so the
+// longitude and the sine of the latitude are drawn uniformly
+fn point_in_rect<R: Rng + ?Sized>(rect: Rect<f64>, rng: &mut R) -> Coord<f64> {
+    let (min, max) = (rect.min(), rect.max());
+    let x = min.x + rng.gen::<f64>() * (max.x - min.x);
+    let (lo, hi) = (sin(min.y.to_radians()), sin(max.y.to_radians()));
+    let y = asin(lo + rng.gen::<f64>() * (hi - lo)).to_degrees();
+    Coord {
+        x,
+        y: y.clamp(min.y, max.y),
+    }
+}
@@ -0,0 +1,134 @@
+use crate::core::{hash_to_int, int_to_hash};
+use crate::{Format, GeohashError};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// ends the geohash part of a sort key, it sorts before every encoded character
+const TERMINATOR: u8 = 0x00;
+
+/// Encode a geohash into an order-preserving byte string.
+///
+/// Each character is stored as one byte (its base32 value plus one) followed by a `0x00`
+/// terminator, so that comparing sort keys byte by byte orders them in Z-order, a geohash sorts
+/// before all of its descendants, and the end of the geohash is known when it's followed by
+/// more data.
+///
+/// ### Examples
+///
+/// ```rust
+/// let parent = geohash::to_sort_key("9q6").unwrap();
+/// let child = geohash::to_sort_key("9q60").unwrap();
+/// let sibling = geohash::to_sort_key("9q7").unwrap();
+///
+/// assert!(parent < child && child < sibling);
+/// assert_eq!(geohash::from_sort_key(&child).unwrap(), ("9q60".to_owned(), 5));
+/// ```
+pub fn to_sort_key(hash_str: &str) -> Result<Vec<u8>, GeohashError> {
+    let mut key = sort_key_prefix(hash_str)?;
+    key.push(TERMINATOR);
+    Ok(key)
+}
+
+/// The bytes every sort key of the given geohash and its descendants starts with.
+///
+/// This is [`to_sort_key`] without the terminator, and is the prefix to scan for to find all
+/// keys inside a geohash cell, including the keys built with [`CompositeKey`].
+pub fn sort_key_prefix(hash_str: &str) -> Result<Vec<u8>, GeohashError> {
+    let int_hash = hash_to_int(hash_str)?;
+    Ok((0..hash_str.len())
+        .rev()
+        .map(|i| ((int_hash >> (5 * i)) & 0x1f) as u8 + 1)
+        .collect())
+}
+
+/// Decode the geohash at the start of a sort key.
+///
+/// Returns the geohash and the number of bytes it took up, including the terminator, so that
+/// any data following it can be read from the rest of the key.
+pub fn from_sort_key(key: &[u8]) -> Result<(String, usize), GeohashError> {
+    let len =
+        key.iter()
+            .position(|&b| b == TERMINATOR)
+            .ok_or_else(|| GeohashError::InvalidFormat {
+                format: Format::SortKey,
+                reason: "missing terminator".into(),
+            })?;
+    if len == 0 || len > 12 {
+        return Err(GeohashError::InvalidFormat {
+            format: Format::SortKey,
+            reason: format!("invalid geohash length: {}", len),
+        });
+    }
+
+    let mut int_hash = 0u64;
+    for &b in &key[..len] {
+        if !(1..=32).contains(&b) {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::SortKey,
+                reason: format!("invalid character byte: {:#04x}", b),
+            });
+        }
+        int_hash = int_hash << 5 | (b - 1) as u64;
+    }
+    Ok((int_to_hash(int_hash, len), len + 1))
+}
+
+/// A sort key made of a geohash, a timestamp and an identifier.
+///
+/// Encoded keys sort by geohash first (in the same order as [`to_sort_key`]), then by
+/// timestamp and finally by the raw identifier bytes, so a prefix scan with
+/// [`sort_key_prefix`] returns exactly the keys inside a cell.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{sort_key_prefix, CompositeKey};
+///
+/// let key = CompositeKey {
+///     geohash: "9q60y60rhs".to_owned(),
+///     timestamp: 1_700_000_000,
+///     id: b"truck-17".to_vec(),
+/// };
+/// let bytes = key.to_bytes().unwrap();
+///
+/// assert!(bytes.starts_with(&sort_key_prefix("9q60y").unwrap()));
+/// assert_eq!(CompositeKey::from_bytes(&bytes).unwrap(), key);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CompositeKey {
+    pub geohash: String,
+    pub timestamp: u64,
+    pub id: Vec<u8>,
+}
+
+impl CompositeKey {
+    /// Encode the key into its byte representation.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, GeohashError> {
+        let mut bytes = to_sort_key(&self.geohash)?;
+        bytes.reserve(8 + self.id.len());
+        // big endian, so that the byte order matches the numeric order
+        bytes.extend_from_slice(&self.timestamp.to_be_bytes());
+        bytes.extend_from_slice(&self.id);
+        Ok(bytes)
+    }
+
+    /// Decode a key from the bytes produced by [`CompositeKey::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, GeohashError> {
+        let (geohash, offset) = from_sort_key(bytes)?;
+        let rest = &bytes[offset..];
+        if rest.len() < 8 {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::SortKey,
+                reason: "missing timestamp".into(),
+            });
+        }
+        let mut timestamp = [0; 8];
+        timestamp.copy_from_slice(&rest[..8]);
+        Ok(CompositeKey {
+            geohash,
+            timestamp: u64::from_be_bytes(timestamp),
+            id: rest[8..].to_vec(),
+        })
+    }
+}
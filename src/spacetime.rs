@@ -0,0 +1,280 @@
+use crate::core::{bbox_int_with_precision, char_value, hash_to_int, int_to_hash, value_char};
+use crate::ranges::{classify, refine, Coverage};
+use crate::{decode_bbox, encode_iter, Coord, Format, GeohashError, Rect};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Range, RangeInclusive};
+
+/// A half-open range of timestamps, in seconds.
+pub type TimeRange = Range<u64>;
+
+/// How the characters of the geohash and of the time bucket are laid out in a
+/// [`SpaceTimeKey`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyLayout {
+    /// The geohash and time characters alternate, starting with the geohash, so that keys
+    /// close in space and in time tend to be close in key order.
+    Interleaved,
+    /// The geohash comes first, followed by the time bucket, so that all the keys of a cell
+    /// are together, sorted by time.
+    Concatenated,
+}
+
+/// A scheme for spatio-temporal keys, combining a geohash with the index of the time bucket of
+/// a timestamp.
+///
+/// Time buckets of `bucket` seconds are counted from the Unix epoch and written with the
+/// geohash alphabet in a fixed number of characters, so keys are strings of the same length
+/// that sort in the same order as their integer form, 5 bits per character.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{Coord, KeyLayout, SpaceTimeKey};
+///
+/// // geohashes of 6 characters and hourly buckets
+/// let scheme = SpaceTimeKey::new(KeyLayout::Concatenated, 6, 3600, 4).unwrap();
+/// let c = Coord { x: -120.6623, y: 35.3003 };
+///
+/// let key = scheme.encode(c, 1_700_000_000).unwrap();
+/// assert_eq!(key, "9q60y6fe4y");
+///
+/// let (rect, time) = scheme.decode(&key).unwrap();
+/// assert_eq!(rect, geohash::decode_bbox("9q60y6").unwrap());
+/// assert_eq!(time, 1_699_999_200..1_700_002_800);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpaceTimeKey {
+    layout: KeyLayout,
+    geohash_len: usize,
+    bucket: u64,
+    time_len: usize,
+}
+
+impl SpaceTimeKey {
+    /// Create a scheme of geohashes of `geohash_len` characters and time buckets of `bucket`
+    /// seconds, written in `time_len` characters. Keys can be at most 12 characters long, and
+    /// the last of the `32^time_len` time buckets must end at a timestamp that fits in a `u64`.
+    pub fn new(
+        layout: KeyLayout,
+        geohash_len: usize,
+        bucket: u64,
+        time_len: usize,
+    ) -> Result<SpaceTimeKey, GeohashError> {
+        let invalid = |reason: String| GeohashError::InvalidFormat {
+            format: Format::SpaceTimeKey,
+            reason,
+        };
+        if geohash_len == 0 {
+            return Err(invalid("geohash length 0, must be at least 1".into()));
+        }
+        if time_len == 0 {
+            return Err(invalid("time length 0, must be at least 1".into()));
+        }
+        if geohash_len + time_len > 12 {
+            return Err(invalid(format!(
+                "geohash length {} and time length {} add up to more than 12",
+                geohash_len, time_len
+            )));
+        }
+        if bucket == 0 {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Time,
+                reason: "empty time bucket".into(),
+            });
+        }
+        // the end of the last time bucket must be a timestamp too
+        if (bucket as u128) << (5 * time_len) > u64::MAX as u128 {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Time,
+                reason: format!(
+                    "time buckets of {} seconds overflow with a time length of {}",
+                    bucket, time_len
+                ),
+            });
+        }
+        Ok(SpaceTimeKey {
+            layout,
+            geohash_len,
+            bucket,
+            time_len,
+        })
+    }
+
+    /// The number of characters of the keys.
+    pub fn key_len(&self) -> usize {
+        self.geohash_len + self.time_len
+    }
+
+    /// The number of bits of the integer keys.
+    pub fn bits(&self) -> u32 {
+        self.key_len() as u32 * 5
+    }
+
+    /// Encode a coordinate and a timestamp, in seconds, to a key.
+    pub fn encode(&self, c: Coord<f64>, timestamp: u64) -> Result<String, GeohashError> {
+        let index = timestamp / self.bucket;
+        if index >> (5 * self.time_len) != 0 {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Time,
+                reason: format!("timestamp {} is past the last time bucket", timestamp),
+            });
+        }
+        let mut geohash = encode_iter(c)?.take(self.geohash_len);
+        let mut time = (0..self.time_len)
+            .rev()
+            .map(|i| value_char((index >> (5 * i)) as u8 & 0x1f));
+        Ok(self
+            .slots()
+            .map(|is_space| {
+                if is_space {
+                    geohash.next()
+                } else {
+                    time.next()
+                }
+            })
+            .collect::<Option<String>>()
+            .expect("the slots match the lengths of the geohash and time bucket"))
+    }
+
+    /// Encode a coordinate and a timestamp, in seconds, to an integer key.
+    pub fn encode_int(&self, c: Coord<f64>, timestamp: u64) -> Result<u64, GeohashError> {
+        hash_to_int(&self.encode(c, timestamp)?)
+    }
+
+    /// Write an integer key as a string key.
+    pub fn int_to_key(&self, int_key: u64) -> String {
+        int_to_hash(int_key & (u64::MAX >> (64 - self.bits())), self.key_len())
+    }
+
+    /// Decode a key into the bounding box of its geohash and its time bucket.
+    pub fn decode(&self, key: &str) -> Result<(Rect<f64>, TimeRange), GeohashError> {
+        if key.chars().count() != self.key_len() {
+            return Err(GeohashError::InvalidHash(format!(
+                "expected a key of {} characters, got {:?}",
+                self.key_len(),
+                key
+            )));
+        }
+        let mut geohash = String::with_capacity(self.geohash_len);
+        let mut index = 0;
+        for (c, is_space) in key.chars().zip(self.slots()) {
+            if is_space {
+                geohash.push(c);
+            } else {
+                index = index << 5 | char_value(c)? as u64;
+            }
+        }
+        Ok((decode_bbox(&geohash)?, self.bucket_range(index, 1)))
+    }
+
+    /// Decode an integer key into the bounding box of its geohash and its time bucket.
+    pub fn decode_int(&self, int_key: u64) -> Result<(Rect<f64>, TimeRange), GeohashError> {
+        self.decode(&self.int_to_key(int_key))
+    }
+
+    /// Decompose a query for a rectangle and a time range into ranges of integer keys, like
+    /// [`rect_to_ranges`](crate::rect_to_ranges) does for integer geohashes.
+    ///
+    /// Every key of a coordinate inside `rect` and a timestamp inside `time` is inside one of
+    /// the at most `max_ranges` returned `[lo, hi]` ranges, which are sorted and never overlap
+    /// or touch. Their ends are inclusive, like the ones of integer geohashes.
+    ///
+    /// ### Examples
+    ///
+    /// ```
+    /// use geohash::{Coord, KeyLayout, Rect, SpaceTimeKey};
+    ///
+    /// let scheme = SpaceTimeKey::new(KeyLayout::Interleaved, 7, 60, 5).unwrap();
+    /// let rect = Rect::new(Coord { x: -122.5, y: 37.7 }, Coord { x: -122.3, y: 37.8 });
+    /// let ranges = scheme.rect_to_ranges(rect, 1_700_000_000..1_700_086_400, 16).unwrap();
+    /// assert!(ranges.len() <= 16);
+    ///
+    /// let key = scheme.encode_int(Coord { x: -122.4, y: 37.75 }, 1_700_040_000).unwrap();
+    /// assert!(ranges.iter().any(|r| r.contains(&key)));
+    /// ```
+    pub fn rect_to_ranges(
+        &self,
+        rect: Rect<f64>,
+        time: TimeRange,
+        max_ranges: usize,
+    ) -> Result<Vec<RangeInclusive<u64>>, GeohashError> {
+        for c in [rect.min(), rect.max()] {
+            if !(-180.0..=180.0).contains(&c.x) || !(-90.0..=90.0).contains(&c.y) {
+                return Err(GeohashError::InvalidCoordinateRange(c));
+            }
+        }
+        if time.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(refine(self.bits(), max_ranges, |prefix, depth| {
+            let (space, bucket_range) = self.split_prefix(prefix, depth);
+            let overlaps = bucket_range.start < time.end && time.start < bucket_range.end;
+            let inside = time.start <= bucket_range.start && bucket_range.end <= time.end;
+            match (classify(space, rect), overlaps, inside) {
+                (Coverage::Outside, _, _) | (_, false, _) => Coverage::Outside,
+                (Coverage::Inside, _, true) => Coverage::Inside,
+                _ => Coverage::Partial,
+            }
+        }))
+    }
+
+    // whether each character of a key belongs to the geohash or the time bucket
+    fn slots(&self) -> impl Iterator<Item = bool> {
+        let (geohash_len, time_len) = (self.geohash_len, self.time_len);
+        let interleaved = self.layout == KeyLayout::Interleaved;
+        (0..geohash_len + time_len).map(move |i| {
+            if interleaved {
+                // pairs of characters while both parts last, then the rest of the longer one
+                let pairs = geohash_len.min(time_len);
+                if i < 2 * pairs {
+                    i % 2 == 0
+                } else {
+                    geohash_len > time_len
+                }
+            } else {
+                i < geohash_len
+            }
+        })
+    }
+
+    // the times covered by `count` buckets starting at the bucket `index`
+    fn bucket_range(&self, index: u64, count: u64) -> TimeRange {
+        // `new` checks that the end of the last bucket doesn't overflow
+        index * self.bucket..(index + count) * self.bucket
+    }
+
+    // splits the first `depth` bits of a key into the bounding box of its geohash bits, and
+    // the times covered by its time bits
+    fn split_prefix(&self, prefix: u64, depth: u32) -> (Rect<f64>, TimeRange) {
+        let (mut space, mut space_bits) = (0u64, 0u32);
+        let (mut time, mut time_bits) = (0u64, 0u32);
+        let mut remaining = depth;
+        for is_space in self.slots() {
+            let n = remaining.min(5);
+            remaining -= n;
+            let value = (prefix >> remaining) & ((1 << n) - 1);
+            if is_space {
+                space = space << n | value;
+                space_bits += n;
+            } else {
+                time = time << n | value;
+                time_bits += n;
+            }
+        }
+        let bbox = if space_bits == 0 {
+            Rect::new(
+                Coord {
+                    x: -180.0,
+                    y: -90.0,
+                },
+                Coord { x: 180.0, y: 90.0 },
+            )
+        } else {
+            bbox_int_with_precision(space, space_bits)
+        };
+        let free_bits = 5 * self.time_len as u32 - time_bits;
+        (bbox, self.bucket_range(time << free_bits, 1 << free_bits))
+    }
+}
@@ -0,0 +1,203 @@
+use crate::ranges::cell_span;
+use crate::{cover_rect, decode_bbox, Coord, Format, GeohashError, Rect};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use core::fmt;
+use core::ops::RangeInclusive;
+use libm::{asinh, atan, sinh, tan};
+
+/// The latitude limit of the Web Mercator projection, where the map becomes square.
+pub const MERCATOR_MAX_LAT: f64 = 85.05112877980659;
+
+const MAX_ZOOM: u8 = 30;
+
+/// A Web Mercator map tile, as used by XYZ ("slippy map") tile servers and Bing quadkeys.
+///
+/// `x` counts columns eastward from the antimeridian and `y` counts rows southward from the
+/// northern limit of the projection, both from zero up to `2^z`.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::Tile;
+///
+/// let tile = Tile::from_quadkey("213").unwrap();
+/// assert_eq!(tile, Tile { x: 3, y: 5, z: 3 });
+/// assert_eq!(tile.quadkey(), "213");
+/// assert_eq!(tile.to_string(), "3/3/5");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub z: u8,
+}
+
+impl Tile {
+    /// Create a tile, checking that its coordinates exist at its zoom level.
+    pub fn new(x: u32, y: u32, z: u8) -> Result<Tile, GeohashError> {
+        if z > MAX_ZOOM || x as u64 >= 1 << z || y as u64 >= 1 << z {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Tile,
+                reason: format!("{}/{}/{}", z, x, y),
+            });
+        }
+        Ok(Tile { x, y, z })
+    }
+
+    /// Parse a Bing Maps quadkey, whose length is the zoom level of the tile.
+    pub fn from_quadkey(quadkey: &str) -> Result<Tile, GeohashError> {
+        if quadkey.len() > MAX_ZOOM as usize {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Tile,
+                reason: quadkey.into(),
+            });
+        }
+        let (mut x, mut y) = (0, 0);
+        for digit in quadkey.chars() {
+            let d = digit
+                .to_digit(4)
+                .ok_or_else(|| GeohashError::InvalidFormat {
+                    format: Format::Tile,
+                    reason: quadkey.into(),
+                })?;
+            x = x << 1 | (d & 1);
+            y = y << 1 | (d >> 1);
+        }
+        Ok(Tile {
+            x,
+            y,
+            z: quadkey.len() as u8,
+        })
+    }
+
+    /// The Bing Maps quadkey of the tile.
+    pub fn quadkey(&self) -> String {
+        (0..self.z)
+            .rev()
+            .map(|i| {
+                let digit = (self.x >> i & 1) | (self.y >> i & 1) << 1;
+                (b'0' + digit as u8) as char
+            })
+            .collect()
+    }
+
+    /// The bounding box of the tile in longitude and latitude.
+    pub fn bbox(&self) -> Rect<f64> {
+        let n = (1u64 << self.z) as f64;
+        Rect::new(
+            Coord {
+                x: self.x as f64 / n * 360.0 - 180.0,
+                y: mercator_lat(self.y as f64 + 1.0, n),
+            },
+            Coord {
+                x: (self.x as f64 + 1.0) / n * 360.0 - 180.0,
+                y: mercator_lat(self.y as f64, n),
+            },
+        )
+    }
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.z, self.x, self.y)
+    }
+}
+
+// the fractional tile row of a latitude
+fn mercator_y(lat: f64, n: f64) -> f64 {
+    let lat = lat.clamp(-MERCATOR_MAX_LAT, MERCATOR_MAX_LAT).to_radians();
+    (1.0 - asinh(tan(lat)) / PI) / 2.0 * n
+}
+
+// the latitude of a fractional tile row
+fn mercator_lat(y: f64, n: f64) -> f64 {
+    atan(sinh(PI * (1.0 - 2.0 * y / n))).to_degrees()
+}
+
+/// Find the tiles at zoom level `zoom` covering a geohash.
+///
+/// Parts of the geohash beyond the latitude limit of Web Mercator have no tiles, so a geohash
+/// lying entirely beyond it is covered by no tiles at all.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{hash_to_tiles, Tile};
+///
+/// assert_eq!(hash_to_tiles("u", 2).unwrap(), [Tile { x: 2, y: 0, z: 2 }, Tile { x: 2, y: 1, z: 2 }]);
+/// ```
+pub fn hash_to_tiles(hash_str: &str, zoom: u8) -> Result<Vec<Tile>, GeohashError> {
+    if zoom > MAX_ZOOM {
+        return Err(GeohashError::InvalidFormat {
+            format: Format::Tile,
+            reason: format!("zoom level {}", zoom),
+        });
+    }
+    let rect = decode_bbox(hash_str)?;
+    Ok(match tile_span(rect, zoom) {
+        Some((rows, cols)) => rows
+            .flat_map(|y| cols.clone().map(move |x| Tile { x, y, z: zoom }))
+            .collect(),
+        None => Vec::new(),
+    })
+}
+
+// the rows and columns of the tiles at `zoom` overlapping a rectangle
+fn tile_span(rect: Rect<f64>, zoom: u8) -> Option<(RangeInclusive<u32>, RangeInclusive<u32>)> {
+    let (min, max) = (rect.min(), rect.max());
+    if min.y >= MERCATOR_MAX_LAT || max.y <= -MERCATOR_MAX_LAT {
+        return None;
+    }
+    let n = (1u64 << zoom) as f64;
+    Some((
+        cell_span(mercator_y(max.y, n), mercator_y(min.y, n), 1.0, n),
+        cell_span(min.x + 180.0, max.x + 180.0, 360.0 / n, 360.0),
+    ))
+}
+
+/// Find the smallest tile containing a whole geohash, or `None` if the geohash lies beyond the
+/// latitude limit of Web Mercator.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{hash_to_tile, Tile};
+///
+/// assert_eq!(hash_to_tile("u").unwrap(), Some(Tile { x: 1, y: 0, z: 1 }));
+/// assert_eq!(hash_to_tile("zzz").unwrap(), None);
+/// ```
+pub fn hash_to_tile(hash_str: &str) -> Result<Option<Tile>, GeohashError> {
+    let rect = decode_bbox(hash_str)?;
+    let mut found = None;
+    for zoom in 0..=MAX_ZOOM {
+        match tile_span(rect, zoom) {
+            Some((rows, cols)) if rows.start() == rows.end() && cols.start() == cols.end() => {
+                found = Some(Tile {
+                    x: *cols.start(),
+                    y: *rows.start(),
+                    z: zoom,
+                });
+            }
+            _ => break,
+        }
+    }
+    Ok(found)
+}
+
+/// Find the geohashes of length `len` covering a tile.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{tile_to_hashes, Tile};
+///
+/// // the tile between the equator and the latitude of 66.5, east of Greenwich
+/// let tile = Tile::new(2, 1, 2).unwrap();
+/// assert_eq!(tile_to_hashes(tile, 1).unwrap(), ["s", "t", "u", "v"]);
+/// ```
+pub fn tile_to_hashes(tile: Tile, len: usize) -> Result<Vec<String>, GeohashError> {
+    cover_rect(tile.bbox(), len)
+}
@@ -0,0 +1,169 @@
+use crate::core::{
+    encode_int, error_with_precision, grid_bits, hash_to_int, int_to_grid, int_to_hash,
+};
+use crate::{Coord, Format, GeohashError};
+use alloc::format;
+use alloc::vec::Vec;
+
+/// Encode a track into a compact byte string, with the geohashes of length `len` of its points.
+///
+/// The first byte is `len` and the next `len` bytes are the geohash of the first point. Every
+/// other point is stored as the offset from the cell of the previous point, in columns and
+/// rows of the grid of geohashes of that length, written like in Google's polyline encoding:
+/// zig-zag encoded so that small negative offsets stay small, then in groups of 7 bits with the
+/// high bit of every byte but the last set. Nearby points then only take two bytes. Offsets
+/// between columns take the shortest way around the antimeridian.
+///
+/// ### Examples
+///
+/// ```rust
+/// use geohash::{decode_track, encode_track, Coord};
+///
+/// let track = [
+///     Coord { x: 2.2945, y: 48.8582 },
+///     Coord { x: 2.2950, y: 48.8590 },
+///     Coord { x: 2.2971, y: 48.8601 },
+/// ];
+/// let bytes = encode_track(track.iter().copied(), 8).unwrap();
+/// assert_eq!(bytes.len(), 1 + 8 + 2 + 2);
+///
+/// // every point is decoded to the center of its cell
+/// let decoded = decode_track(&bytes).unwrap();
+/// let (_, lon_err, lat_err) = geohash::decode("u09tunq6").unwrap();
+/// for (c, d) in track.iter().zip(decoded) {
+///     assert!((c.x - d.x).abs() <= lon_err && (c.y - d.y).abs() <= lat_err);
+/// }
+/// ```
+pub fn encode_track<I: IntoIterator<Item = Coord<f64>>>(
+    track: I,
+    len: usize,
+) -> Result<Vec<u8>, GeohashError> {
+    if !(1..=12).contains(&len) {
+        return Err(GeohashError::InvalidLength(len));
+    }
+    let bits = len as u32 * 5;
+    let (_, lon_bits) = grid_bits(bits);
+    let cols = 1i64 << lon_bits;
+
+    let mut bytes = Vec::from([len as u8]);
+    let mut previous: Option<(i64, i64)> = None;
+    for c in track {
+        let int_hash = encode_int(c, bits)?;
+        let (row, col) = int_to_grid(int_hash, bits);
+        let (row, col) = (row as i64, col as i64);
+        match previous {
+            None => bytes.extend_from_slice(int_to_hash(int_hash, len).as_bytes()),
+            Some((prev_row, prev_col)) => {
+                // the shortest way around the world
+                let dcol = (col - prev_col + cols / 2).rem_euclid(cols) - cols / 2;
+                write_varint(&mut bytes, dcol);
+                write_varint(&mut bytes, row - prev_row);
+            }
+        }
+        previous = Some((row, col));
+    }
+    Ok(bytes)
+}
+
+/// Decode a track encoded with [`encode_track`] into the centers of the cells of its points.
+///
+/// Every point is within the error returned by [`decode`](crate::decode) for geohashes of the
+/// length of the track from the original point.
+pub fn decode_track(bytes: &[u8]) -> Result<Vec<Coord<f64>>, GeohashError> {
+    let (&len, rest) = bytes
+        .split_first()
+        .ok_or_else(|| GeohashError::InvalidFormat {
+            format: Format::Track,
+            reason: "missing length".into(),
+        })?;
+    let len = len as usize;
+    if !(1..=12).contains(&len) {
+        return Err(GeohashError::InvalidLength(len));
+    }
+    let mut track = Vec::new();
+    if rest.is_empty() {
+        return Ok(track);
+    }
+    if rest.len() < len {
+        return Err(GeohashError::InvalidFormat {
+            format: Format::Track,
+            reason: "truncated first geohash".into(),
+        });
+    }
+    let (first, mut rest) = rest.split_at(len);
+    let first = core::str::from_utf8(first)
+        .ok()
+        .filter(|first| first.is_ascii())
+        .ok_or_else(|| GeohashError::InvalidFormat {
+            format: Format::Track,
+            reason: "first geohash is not ASCII".into(),
+        })?;
+
+    let bits = len as u32 * 5;
+    let (lat_bits, lon_bits) = grid_bits(bits);
+    let (rows, cols) = (1i64 << lat_bits, 1i64 << lon_bits);
+    let (height, width) = error_with_precision(bits);
+    let center = |row: i64, col: i64| Coord {
+        x: (col as f64 + 0.5) * width - 180.0,
+        y: (row as f64 + 0.5) * height - 90.0,
+    };
+
+    let (row, col) = int_to_grid(hash_to_int(first)?, bits);
+    let (mut row, mut col) = (row as i64, col as i64);
+    track.push(center(row, col));
+    while !rest.is_empty() {
+        let dcol = read_varint(&mut rest)?;
+        let drow = read_varint(&mut rest)?;
+        // the number of columns is a power of two, so wrapping around is exact
+        col = col.wrapping_add(dcol).rem_euclid(cols);
+        row = match row.checked_add(drow) {
+            Some(row) if (0..rows).contains(&row) => row,
+            _ => {
+                return Err(GeohashError::InvalidFormat {
+                    format: Format::Track,
+                    reason: format!("row offset {} leaves the grid", drow),
+                })
+            }
+        };
+        track.push(center(row, col));
+    }
+    Ok(track)
+}
+
+fn write_varint(bytes: &mut Vec<u8>, value: i64) {
+    // zig-zag: 0, -1, 1, -2, 2, ... become 0, 1, 2, 3, 4, ...
+    let mut v = ((value << 1) ^ (value >> 63)) as u64;
+    while v >= 0x80 {
+        bytes.push((v as u8 & 0x7f) | 0x80);
+        v >>= 7;
+    }
+    bytes.push(v as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<i64, GeohashError> {
+    let mut v = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&b, rest) = bytes
+            .split_first()
+            .ok_or_else(|| GeohashError::InvalidFormat {
+                format: Format::Track,
+                reason: "truncated offset".into(),
+            })?;
+        *bytes = rest;
+        // the tenth byte only holds the last bit of the 64
+        if shift == 63 && b > 1 {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::Track,
+                reason: "offset overflows 64 bits".into(),
+            });
+        }
+        v |= ((b & 0x7f) as u64) << shift;
+        if b & 0x80 == 0 {
+            return Ok((v >> 1) as i64 ^ -((v & 1) as i64));
+        }
+    }
+    Err(GeohashError::InvalidFormat {
+        format: Format::Track,
+        reason: "offset is too long".into(),
+    })
+}
@@ -0,0 +1,346 @@
+use crate::core::{char_value, hash_to_int, value_char};
+use crate::{encode_iter, Coord, GeohashError};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A prefix tree of values keyed by geohash.
+///
+/// Every node of the tree is a geohash cell, and its children are the 32 cells one character
+/// longer. This makes it cheap to find everything inside a cell, to aggregate values to a
+/// coarser precision, and to find which of a set of registered cells of mixed precision
+/// contain a point.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{Coord, GeohashTrie};
+///
+/// let mut zones = GeohashTrie::new();
+/// zones.insert("9q8", "bay area").unwrap();
+/// zones.insert("9q8yy", "downtown").unwrap();
+///
+/// let point = Coord { x: -122.4194, y: 37.7749 };
+/// assert_eq!(zones.longest_match_point(point), Some(("9q8yy".to_owned(), &"downtown")));
+///
+/// let point = Coord { x: -122.43, y: 37.46 };
+/// assert_eq!(zones.longest_match_point(point), Some(("9q8".to_owned(), &"bay area")));
+///
+/// let point = Coord { x: -122.2711, y: 37.8044 };
+/// assert_eq!(zones.longest_match_point(point), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GeohashTrie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    value: Option<V>,
+    // sorted by the base32 value of the character leading to the child
+    children: Vec<(u8, Node<V>)>,
+}
+
+impl<V> Node<V> {
+    fn new() -> Self {
+        Node {
+            value: None,
+            children: Vec::new(),
+        }
+    }
+
+    fn child(&self, code: u8) -> Option<&Node<V>> {
+        self.children
+            .binary_search_by_key(&code, |(c, _)| *c)
+            .ok()
+            .map(|i| &self.children[i].1)
+    }
+
+    fn child_mut(&mut self, code: u8) -> Option<&mut Node<V>> {
+        self.children
+            .binary_search_by_key(&code, |(c, _)| *c)
+            .ok()
+            .map(move |i| &mut self.children[i].1)
+    }
+
+    fn child_or_insert(&mut self, code: u8) -> &mut Node<V> {
+        let i = match self.children.binary_search_by_key(&code, |(c, _)| *c) {
+            Ok(i) => i,
+            Err(i) => {
+                self.children.insert(i, (code, Node::new()));
+                i
+            }
+        };
+        &mut self.children[i].1
+    }
+
+    // pushes the values of this node and all of its descendants in Z-order
+    fn collect<'a>(&'a self, prefix: &mut String, out: &mut Vec<(String, &'a V)>) {
+        if let Some(v) = &self.value {
+            out.push((prefix.clone(), v));
+        }
+        for (code, child) in &self.children {
+            prefix.push(value_char(*code));
+            child.collect(prefix, out);
+            prefix.pop();
+        }
+    }
+
+    fn remove(&mut self, mut codes: impl Iterator<Item = u8>) -> Option<V> {
+        match codes.next() {
+            None => self.value.take(),
+            Some(code) => {
+                let i = self
+                    .children
+                    .binary_search_by_key(&code, |(c, _)| *c)
+                    .ok()?;
+                let removed = self.children[i].1.remove(codes);
+                let child = &self.children[i].1;
+                if child.value.is_none() && child.children.is_empty() {
+                    self.children.remove(i);
+                }
+                removed
+            }
+        }
+    }
+}
+
+// the base32 values of the characters of a geohash
+fn codes(hash_str: &str) -> Result<impl Iterator<Item = u8>, GeohashError> {
+    let int_hash = hash_to_int(hash_str)?;
+    Ok((0..hash_str.len())
+        .rev()
+        .map(move |i| ((int_hash >> (5 * i)) & 0x1f) as u8))
+}
+
+impl<V> Default for GeohashTrie<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> GeohashTrie<V> {
+    /// Create an empty trie.
+    pub fn new() -> Self {
+        GeohashTrie {
+            root: Node::new(),
+            len: 0,
+        }
+    }
+
+    /// The number of geohashes holding a value.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the trie holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert a value at the given geohash, returning the value previously stored there.
+    pub fn insert(&mut self, hash_str: &str, value: V) -> Result<Option<V>, GeohashError> {
+        let node = codes(hash_str)?.fold(&mut self.root, |node, code| node.child_or_insert(code));
+        let previous = node.value.replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        Ok(previous)
+    }
+
+    /// Insert a value at the geohash of length `len` containing the coordinate, returning the
+    /// value previously stored there.
+    pub fn insert_point(
+        &mut self,
+        c: Coord<f64>,
+        len: usize,
+        value: V,
+    ) -> Result<Option<V>, GeohashError> {
+        if !(1..=12).contains(&len) {
+            return Err(GeohashError::InvalidLength(len));
+        }
+        let mut node = &mut self.root;
+        for ch in encode_iter(c)?.take(len) {
+            node = node.child_or_insert(char_value(ch)?);
+        }
+        let previous = node.value.replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        Ok(previous)
+    }
+
+    /// Get the value stored at exactly the given geohash.
+    pub fn get(&self, hash_str: &str) -> Option<&V> {
+        self.node(hash_str)?.value.as_ref()
+    }
+
+    /// Get a mutable reference to the value stored at exactly the given geohash.
+    pub fn get_mut(&mut self, hash_str: &str) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        for code in codes(hash_str).ok()? {
+            node = node.child_mut(code)?;
+        }
+        node.value.as_mut()
+    }
+
+    /// Get the value stored at the given geohash, inserting the result of `default` if there is
+    /// none yet.
+    pub fn get_or_insert_with<F>(
+        &mut self,
+        hash_str: &str,
+        default: F,
+    ) -> Result<&mut V, GeohashError>
+    where
+        F: FnOnce() -> V,
+    {
+        let node = codes(hash_str)?.fold(&mut self.root, |node, code| node.child_or_insert(code));
+        if node.value.is_none() {
+            self.len += 1;
+        }
+        Ok(node.value.get_or_insert_with(default))
+    }
+
+    /// Remove the value stored at exactly the given geohash.
+    pub fn remove(&mut self, hash_str: &str) -> Option<V> {
+        let removed = self.root.remove(codes(hash_str).ok()?);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    fn node(&self, hash_str: &str) -> Option<&Node<V>> {
+        let mut node = &self.root;
+        for code in codes(hash_str).ok()? {
+            node = node.child(code)?;
+        }
+        Some(node)
+    }
+
+    /// All values stored at the given geohash or any of its descendants, in Z-order.
+    ///
+    /// ### Examples
+    ///
+    /// ```
+    /// let mut trie = geohash::GeohashTrie::new();
+    /// trie.insert("u4pruy", 1).unwrap();
+    /// trie.insert("u4pruv", 2).unwrap();
+    /// trie.insert("u4pr", 3).unwrap();
+    /// trie.insert("u4p", 4).unwrap();
+    ///
+    /// let values: Vec<_> = trie.get_prefix("u4pr").into_iter().map(|(_, v)| *v).collect();
+    /// assert_eq!(values, [3, 2, 1]);
+    /// ```
+    pub fn get_prefix(&self, hash_str: &str) -> Vec<(String, &V)> {
+        let mut out = Vec::new();
+        if let Some(node) = self.node(hash_str) {
+            node.collect(&mut String::from(hash_str), &mut out);
+        }
+        out
+    }
+
+    /// All geohashes and values in the trie, in Z-order.
+    pub fn iter(&self) -> impl Iterator<Item = (String, &V)> {
+        let mut out = Vec::with_capacity(self.len);
+        self.root.collect(&mut String::new(), &mut out);
+        out.into_iter()
+    }
+
+    /// Aggregate the values to geohashes of length `len`.
+    ///
+    /// The values of every geohash at least `len` long are folded into their length `len`
+    /// prefix, starting from `init`. Values stored at shorter geohashes can't be assigned to a
+    /// single cell of length `len`, and are folded on their own under their own geohash.
+    ///
+    /// ### Examples
+    ///
+    /// ```
+    /// let mut counts = geohash::GeohashTrie::new();
+    /// counts.insert("u4pruy", 3).unwrap();
+    /// counts.insert("u4pruv", 2).unwrap();
+    /// counts.insert("u4pq", 4).unwrap();
+    ///
+    /// let totals = counts.rollup(3, 0, |sum, count| sum + count);
+    /// assert_eq!(totals.get("u4p"), Some(&9));
+    ///
+    /// let totals = counts.rollup(4, 0, |sum, count| sum + count);
+    /// assert_eq!(totals.iter().collect::<Vec<_>>(), [("u4pq".to_owned(), &4), ("u4pr".to_owned(), &5)]);
+    /// ```
+    pub fn rollup<A, F>(&self, len: usize, init: A, mut fold: F) -> GeohashTrie<A>
+    where
+        A: Clone,
+        F: FnMut(A, &V) -> A,
+    {
+        // values sharing a prefix are next to each other in Z-order
+        let mut groups: Vec<(String, A)> = Vec::new();
+        for (mut hash, value) in self.iter() {
+            hash.truncate(len);
+            match groups.last_mut() {
+                Some((key, acc)) if *key == hash => {
+                    let prev = core::mem::replace(acc, init.clone());
+                    *acc = fold(prev, value);
+                }
+                _ => groups.push((hash, fold(init.clone(), value))),
+            }
+        }
+
+        let mut out = GeohashTrie::new();
+        for (hash, acc) in groups {
+            // the keys are prefixes of valid geohashes, so inserting them can't fail
+            let _ = out.insert(&hash, acc);
+        }
+        out
+    }
+
+    /// The value stored at the longest geohash that is a prefix of `hash_str`, along with
+    /// that geohash.
+    pub fn longest_match(&self, hash_str: &str) -> Option<(String, &V)> {
+        let codes = codes(hash_str).ok()?;
+        self.longest_match_codes(codes)
+    }
+
+    /// The value stored at the longest geohash containing the coordinate, along with that
+    /// geohash.
+    pub fn longest_match_point(&self, c: Coord<f64>) -> Option<(String, &V)> {
+        let codes = encode_iter(c).ok()?.filter_map(|ch| char_value(ch).ok());
+        self.longest_match_codes(codes)
+    }
+
+    // calls `f` with the values stored at every prefix of the path, shortest first
+    pub(crate) fn for_each_prefix<'a, F>(&'a self, codes: impl Iterator<Item = u8>, mut f: F)
+    where
+        F: FnMut(&'a V),
+    {
+        let mut node = &self.root;
+        for code in codes {
+            match node.child(code) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(v) = &node.value {
+                f(v);
+            }
+        }
+    }
+
+    fn longest_match_codes(&self, codes: impl Iterator<Item = u8>) -> Option<(String, &V)> {
+        let mut node = &self.root;
+        let mut prefix = String::new();
+        let mut best = None;
+        for code in codes {
+            match node.child(code) {
+                Some(child) => node = child,
+                None => break,
+            }
+            prefix.push(value_char(code));
+            if let Some(v) = &node.value {
+                best = Some((prefix.len(), v));
+            }
+        }
+        best.map(|(len, v)| {
+            prefix.truncate(len);
+            (prefix, v)
+        })
+    }
+}
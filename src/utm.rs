@@ -0,0 +1,487 @@
+use crate::geometry::in_range;
+use crate::{cover_rect, decode_bbox, Coord, Format, GeohashError, Rect};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use libm::{asinh, atan, atan2, atanh, cos, cosh, floor, pow, sin, sinh, sqrt, tan};
+
+// WGS84 ellipsoid
+const SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+const FLATTENING: f64 = 1.0 / 298.257_223_563;
+
+const SCALE_FACTOR: f64 = 0.9996;
+const FALSE_EASTING: f64 = 500_000.0;
+const FALSE_NORTHING: f64 = 10_000_000.0;
+
+// UTM covers the latitudes between these, the polar regions use UPS instead
+const MIN_LAT: f64 = -80.0;
+const MAX_LAT: f64 = 84.0;
+
+// latitude bands of 8 degrees from 80S, the last one stretching to 84N
+const BANDS: &[u8; 20] = b"CDEFGHJKLMNPQRSTUVWX";
+// the letters of the 100km square columns, repeating every three zones
+const COLUMN_LETTERS: [&[u8; 8]; 3] = [b"ABCDEFGH", b"JKLMNPQR", b"STUVWXYZ"];
+// the letters of the 100km square rows, offset by five in even zones
+const ROW_LETTERS: &[u8; 20] = b"ABCDEFGHJKLMNPQRSTUV";
+const SQUARE_SIZE: f64 = 100_000.0;
+// northings of 100km square rows repeat every 2000km
+const ROW_CYCLE: f64 = SQUARE_SIZE * ROW_LETTERS.len() as f64;
+
+fn invalid(reference: &str) -> GeohashError {
+    GeohashError::InvalidFormat {
+        format: Format::GridReference,
+        reason: reference.into(),
+    }
+}
+
+/// A position on the Universal Transverse Mercator grid of the WGS84 ellipsoid.
+///
+/// Positions are given by their zone number and latitude band, and their easting and northing
+/// in meters. Northings in the southern hemisphere, from band `C` up to band `M`, are measured
+/// from 10,000km south of the equator.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{Coord, Utm};
+///
+/// let utm = Utm::from_coord(Coord { x: 2.2945, y: 48.8582 }).unwrap();
+/// assert_eq!(utm.to_string(), "31U 448252 5411933");
+///
+/// let c = utm.to_coord().unwrap();
+/// assert!((c.x - 2.2945).abs() < 1e-9 && (c.y - 48.8582).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Utm {
+    pub zone: u8,
+    pub band: char,
+    pub easting: f64,
+    pub northing: f64,
+}
+
+impl Utm {
+    /// Project a coordinate onto the UTM grid, in the zone containing it.
+    ///
+    /// Zones follow the exceptions for southwestern Norway and Svalbard. Coordinates south of
+    /// 80S or north of 84N are outside of the grid.
+    pub fn from_coord(c: Coord<f64>) -> Result<Utm, GeohashError> {
+        if !in_range(c) || !(MIN_LAT..=MAX_LAT).contains(&c.y) {
+            return Err(GeohashError::InvalidCoordinateRange(c));
+        }
+        let zone = zone_of(c);
+        let band = BANDS[(floor((c.y - MIN_LAT) / 8.0) as usize).min(BANDS.len() - 1)] as char;
+        let (easting, northing) = project(c, zone);
+        Ok(Utm {
+            zone,
+            band,
+            easting,
+            northing,
+        })
+    }
+
+    /// The coordinate of the position.
+    pub fn to_coord(&self) -> Result<Coord<f64>, GeohashError> {
+        if !(1..=60).contains(&self.zone) || !BANDS.contains(&(self.band as u8)) {
+            return Err(GeohashError::InvalidFormat {
+                format: Format::GridReference,
+                reason: format!("{}{}", self.zone, self.band),
+            });
+        }
+        let c = unproject(self.easting, self.northing, self.zone, self.north());
+        Ok(Coord {
+            x: if c.x > 180.0 {
+                c.x - 360.0
+            } else if c.x < -180.0 {
+                c.x + 360.0
+            } else {
+                c.x
+            },
+            ..c
+        })
+    }
+
+    // whether the band is in the northern hemisphere
+    fn north(&self) -> bool {
+        self.band >= 'N'
+    }
+}
+
+impl fmt::Display for Utm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{} {:.0} {:.0}",
+            self.zone, self.band, self.easting, self.northing
+        )
+    }
+}
+
+// the UTM zone of a coordinate, including the exceptions around Norway
+fn zone_of(c: Coord<f64>) -> u8 {
+    let zone = (floor((c.x + 180.0) / 6.0) as u8 + 1).min(60);
+    if (56.0..64.0).contains(&c.y) && (3.0..12.0).contains(&c.x) {
+        return 32;
+    }
+    if c.y >= 72.0 {
+        match c.x {
+            x if (0.0..9.0).contains(&x) => return 31,
+            x if (9.0..21.0).contains(&x) => return 33,
+            x if (21.0..33.0).contains(&x) => return 35,
+            x if (33.0..42.0).contains(&x) => return 37,
+            _ => {}
+        }
+    }
+    zone
+}
+
+// the longitude of the central meridian of a zone
+fn central_meridian(zone: u8) -> f64 {
+    (zone as f64 - 1.0) * 6.0 - 180.0 + 3.0
+}
+
+// whether a zone has a part in a latitude band: zones 32, 34 and 36 are left out of band X,
+// where their neighbors are widened to cover Svalbard
+fn zone_exists(zone: u8, band: u8) -> bool {
+    !(band == b'X' && matches!(zone, 32 | 34 | 36))
+}
+
+// the longitudes spanned by a zone within a latitude band, including the exceptions
+fn zone_extent(zone: u8, band: u8) -> (f64, f64) {
+    let west = central_meridian(zone) - 3.0;
+    match (band, zone) {
+        (b'V', 31) => (0.0, 3.0),
+        (b'V', 32) => (3.0, 12.0),
+        (b'X', 31) => (0.0, 9.0),
+        (b'X', 33 | 35) => (west - 3.0, west + 9.0),
+        (b'X', 37) => (33.0, 42.0),
+        _ => (west, west + 6.0),
+    }
+}
+
+// the latitudes spanned by a band
+fn band_extent(band: u8) -> (f64, f64) {
+    let index = BANDS.iter().position(|&b| b == band).unwrap_or(0);
+    let south = MIN_LAT + index as f64 * 8.0;
+    (south, if band == b'X' { MAX_LAT } else { south + 8.0 })
+}
+
+// the third flattening, eccentricity, rectifying radius and the coefficients of the Krüger
+// series to the sixth order, from Karney (2011), "Transverse Mercator with an accuracy of a few
+// nanometers"
+struct Series {
+    e: f64,
+    radius: f64,
+    alpha: [f64; 6],
+    beta: [f64; 6],
+}
+
+fn series() -> Series {
+    let n = FLATTENING / (2.0 - FLATTENING);
+    let p = |k: i32| pow(n, k as f64);
+    Series {
+        e: sqrt(FLATTENING * (2.0 - FLATTENING)),
+        radius: SEMI_MAJOR_AXIS / (1.0 + n) * (1.0 + p(2) / 4.0 + p(4) / 64.0 + p(6) / 256.0),
+        alpha: [
+            n / 2.0 - 2.0 / 3.0 * p(2) + 5.0 / 16.0 * p(3) + 41.0 / 180.0 * p(4)
+                - 127.0 / 288.0 * p(5)
+                + 7891.0 / 37800.0 * p(6),
+            13.0 / 48.0 * p(2) - 3.0 / 5.0 * p(3) + 557.0 / 1440.0 * p(4) + 281.0 / 630.0 * p(5)
+                - 1983433.0 / 1935360.0 * p(6),
+            61.0 / 240.0 * p(3) - 103.0 / 140.0 * p(4)
+                + 15061.0 / 26880.0 * p(5)
+                + 167603.0 / 181440.0 * p(6),
+            49561.0 / 161280.0 * p(4) - 179.0 / 168.0 * p(5) + 6601661.0 / 7257600.0 * p(6),
+            34729.0 / 80640.0 * p(5) - 3418889.0 / 1995840.0 * p(6),
+            212378941.0 / 319334400.0 * p(6),
+        ],
+        beta: [
+            n / 2.0 - 2.0 / 3.0 * p(2) + 37.0 / 96.0 * p(3)
+                - 1.0 / 360.0 * p(4)
+                - 81.0 / 512.0 * p(5)
+                + 96199.0 / 604800.0 * p(6),
+            1.0 / 48.0 * p(2) + 1.0 / 15.0 * p(3) - 437.0 / 1440.0 * p(4) + 46.0 / 105.0 * p(5)
+                - 1118711.0 / 3870720.0 * p(6),
+            17.0 / 480.0 * p(3) - 37.0 / 840.0 * p(4) - 209.0 / 4480.0 * p(5)
+                + 5569.0 / 90720.0 * p(6),
+            4397.0 / 161280.0 * p(4) - 11.0 / 504.0 * p(5) - 830251.0 / 7257600.0 * p(6),
+            4583.0 / 161280.0 * p(5) - 108847.0 / 3991680.0 * p(6),
+            20648693.0 / 638668800.0 * p(6),
+        ],
+    }
+}
+
+// the conformal latitude's tangent for a geodetic latitude's tangent
+fn conformal(tau: f64, e: f64) -> f64 {
+    let sigma = sinh(e * atanh(e * tau / sqrt(1.0 + tau * tau)));
+    tau * sqrt(1.0 + sigma * sigma) - sigma * sqrt(1.0 + tau * tau)
+}
+
+// the easting and northing of a coordinate in a zone
+fn project(c: Coord<f64>, zone: u8) -> (f64, f64) {
+    let s = series();
+    let lat = c.y.to_radians();
+    let lon = (c.x - central_meridian(zone)).to_radians();
+
+    let tau = conformal(tan(lat), s.e);
+    let xi_prime = atan2(tau, cos(lon));
+    let eta_prime = asinh(sin(lon) / sqrt(tau * tau + cos(lon) * cos(lon)));
+
+    let (mut xi, mut eta) = (xi_prime, eta_prime);
+    for (j, alpha) in s.alpha.iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        xi += alpha * sin(k * xi_prime) * cosh(k * eta_prime);
+        eta += alpha * cos(k * xi_prime) * sinh(k * eta_prime);
+    }
+
+    let easting = SCALE_FACTOR * s.radius * eta + FALSE_EASTING;
+    let mut northing = SCALE_FACTOR * s.radius * xi;
+    if c.y < 0.0 {
+        northing += FALSE_NORTHING;
+    }
+    (easting, northing)
+}
+
+// the coordinate of an easting and northing in a zone
+fn unproject(easting: f64, northing: f64, zone: u8, north: bool) -> Coord<f64> {
+    let s = series();
+    let northing = if north {
+        northing
+    } else {
+        northing - FALSE_NORTHING
+    };
+    let eta = (easting - FALSE_EASTING) / (SCALE_FACTOR * s.radius);
+    let xi = northing / (SCALE_FACTOR * s.radius);
+
+    let (mut xi_prime, mut eta_prime) = (xi, eta);
+    for (j, beta) in s.beta.iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        xi_prime -= beta * sin(k * xi) * cosh(k * eta);
+        eta_prime -= beta * cos(k * xi) * sinh(k * eta);
+    }
+
+    let tau_prime =
+        sin(xi_prime) / sqrt(sinh(eta_prime) * sinh(eta_prime) + cos(xi_prime) * cos(xi_prime));
+    // Newton's method on the conformal latitude, which converges in a few steps
+    let e2 = s.e * s.e;
+    let mut tau = tau_prime;
+    for _ in 0..10 {
+        let tau_i = conformal(tau, s.e);
+        let delta = (tau_prime - tau_i) / sqrt(1.0 + tau_i * tau_i)
+            * (1.0 + (1.0 - e2) * tau * tau)
+            / ((1.0 - e2) * sqrt(1.0 + tau * tau));
+        tau += delta;
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+
+    // longitudes are left unwrapped past the antimeridian
+    Coord {
+        x: atan2(sinh(eta_prime), cos(xi_prime)).to_degrees() + central_meridian(zone),
+        y: atan(tau).to_degrees(),
+    }
+}
+
+/// Encode a coordinate to a Military Grid Reference System reference with `digits` digits of
+/// easting and northing each, from 0 for a 100km square down to 5 for a 1m square.
+///
+/// The reference is written without spaces, and its easting and northing are truncated to
+/// the square containing the coordinate.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::{encode_mgrs, Coord};
+///
+/// let c = Coord { x: 2.2945, y: 48.8582 };
+/// assert_eq!(encode_mgrs(c, 5).unwrap(), "31UDQ4825111932");
+/// assert_eq!(encode_mgrs(c, 2).unwrap(), "31UDQ4811");
+/// ```
+pub fn encode_mgrs(c: Coord<f64>, digits: usize) -> Result<String, GeohashError> {
+    if digits > 5 {
+        return Err(GeohashError::InvalidFormat {
+            format: Format::GridReference,
+            reason: format!("invalid number of digits: {}", digits),
+        });
+    }
+    let utm = Utm::from_coord(c)?;
+    let set = (utm.zone as usize - 1) % 3;
+    let column = (floor(utm.easting / SQUARE_SIZE) as usize).clamp(1, 8);
+    let row = floor(utm.northing / SQUARE_SIZE) as usize + (utm.zone as usize + 1) % 2 * 5;
+
+    let mut reference = format!(
+        "{}{}{}{}",
+        utm.zone,
+        utm.band,
+        COLUMN_LETTERS[set][column - 1] as char,
+        ROW_LETTERS[row % ROW_LETTERS.len()] as char,
+    );
+    if digits > 0 {
+        let unit = pow(10.0, 5.0 - digits as f64);
+        let offset = |v: f64| floor(v % SQUARE_SIZE / unit) as u32;
+        reference += &format!(
+            "{:0width$}{:0width$}",
+            offset(utm.easting),
+            offset(utm.northing),
+            width = digits
+        );
+    }
+    Ok(reference)
+}
+
+// the parts of a grid reference: its zone, band, the easting and northing of the south-west
+// corner of its square, and the size of the square in meters
+fn parse_mgrs(reference: &str) -> Result<(u8, char, f64, f64, f64), GeohashError> {
+    let compact: String = reference
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    // the checks below count bytes, and slice at them
+    if !compact.is_ascii() {
+        return Err(invalid(reference));
+    }
+    let bytes = compact.as_bytes();
+    let zone_len = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    if !(1..=2).contains(&zone_len) || bytes.len() < zone_len + 3 {
+        return Err(invalid(reference));
+    }
+    let zone: u8 = compact[..zone_len]
+        .parse()
+        .map_err(|_| invalid(reference))?;
+    let (band, column, row) = (bytes[zone_len], bytes[zone_len + 1], bytes[zone_len + 2]);
+    let numbers = &compact[zone_len + 3..];
+    let set = (zone as usize).wrapping_sub(1) % 3;
+    if !(1..=60).contains(&zone)
+        || !BANDS.contains(&band)
+        || !zone_exists(zone, band)
+        || numbers.len() % 2 == 1
+        || numbers.len() > 10
+        || !numbers.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid(reference));
+    }
+    let column = COLUMN_LETTERS[set]
+        .iter()
+        .position(|&l| l == column)
+        .ok_or_else(|| invalid(reference))?;
+    let row = ROW_LETTERS
+        .iter()
+        .position(|&l| l == row)
+        .ok_or_else(|| invalid(reference))?;
+    let row = (row + ROW_LETTERS.len() - (zone as usize + 1) % 2 * 5) % ROW_LETTERS.len();
+
+    let digits = numbers.len() / 2;
+    let unit = pow(10.0, 5.0 - digits as f64);
+    let parse = |s: &str| s.parse::<u32>().map_or(0.0, |v| v as f64 * unit);
+    let easting = (column + 1) as f64 * SQUARE_SIZE + parse(&numbers[..digits]);
+    let northing = row as f64 * SQUARE_SIZE + parse(&numbers[digits..]);
+
+    // the row letters repeat every 2000km, pick the cycle closest to the middle of the band
+    let (south, north) = band_extent(band);
+    let middle = Coord {
+        x: central_meridian(zone),
+        y: (south + north) / 2.0,
+    };
+    let (_, target) = project(middle, zone);
+    let cycles = floor((target - northing) / ROW_CYCLE + 0.5);
+    Ok((
+        zone,
+        band as char,
+        easting,
+        northing + cycles * ROW_CYCLE,
+        unit,
+    ))
+}
+
+/// Decode a Military Grid Reference System reference into the bounding box of its square.
+///
+/// Spaces in the reference are ignored. As grid squares are not aligned with meridians and
+/// parallels, the bounding box extends slightly beyond the square, except at the edges of
+/// the square's zone and latitude band where the square is cut off.
+///
+/// ### Examples
+///
+/// ```
+/// use geohash::decode_mgrs;
+///
+/// let bbox = decode_mgrs("31U DQ 48251 11932").unwrap();
+/// assert!((bbox.center().x - 2.2945).abs() < 1e-4);
+/// assert!((bbox.center().y - 48.8582).abs() < 1e-4);
+/// ```
+pub fn decode_mgrs(reference: &str) -> Result<Rect<f64>, GeohashError> {
+    let (zone, band, easting, northing, size) = parse_mgrs(reference)?;
+    let north = band >= 'N';
+    // follow the outline of the square, as its edges curve slightly in longitude and latitude
+    const STEPS: u32 = 4;
+    let mut min = unproject(easting, northing, zone, north);
+    let mut max = min;
+    for i in 0..STEPS {
+        let t = i as f64 / STEPS as f64 * size;
+        for (e, n) in [
+            (easting + t, northing),
+            (easting + size, northing + t),
+            (easting + size - t, northing + size),
+            (easting, northing + size - t),
+        ] {
+            let c = unproject(e, n, zone, north);
+            min = Coord {
+                x: min.x.min(c.x),
+                y: min.y.min(c.y),
+            };
+            max = Coord {
+                x: max.x.max(c.x),
+                y: max.y.max(c.y),
+            };
+        }
+    }
+
+    // squares are cut off at the edges of their zone and band
+    let (west, east) = zone_extent(zone, band as u8);
+    let (south, north) = band_extent(band as u8);
+    let (min, max) = (
+        Coord {
+            x: min.x.max(west),
+            y: min.y.max(south),
+        },
+        Coord {
+            x: max.x.min(east),
+            y: max.y.min(north),
+        },
+    );
+    if min.x >= max.x || min.y >= max.y {
+        return Err(invalid(reference));
+    }
+    Ok(Rect::new(min, max))
+}
+
+/// Express the center of a geohash as a Military Grid Reference System reference with
+/// `digits` digits of easting and northing each.
+///
+/// ### Examples
+///
+/// ```
+/// assert_eq!(geohash::hash_to_mgrs("u09tunq", 3).unwrap(), "31UDQ482119");
+/// ```
+pub fn hash_to_mgrs(hash_str: &str, digits: usize) -> Result<String, GeohashError> {
+    encode_mgrs(decode_bbox(hash_str)?.center(), digits)
+}
+
+/// Express the center of a geohash on the UTM grid.
+pub fn hash_to_utm(hash_str: &str) -> Result<Utm, GeohashError> {
+    Utm::from_coord(decode_bbox(hash_str)?.center())
+}
+
+/// Find the geohashes of length `len` covering the square of a Military Grid Reference System
+/// reference.
+///
+/// ### Examples
+///
+/// ```
+/// let hashes = geohash::mgrs_to_hashes("31UDQ4811", 6).unwrap();
+/// assert_eq!(hashes, ["u09tuh", "u09tuj", "u09tuk", "u09tum", "u09tun", "u09tuq"]);
+/// ```
+pub fn mgrs_to_hashes(reference: &str, len: usize) -> Result<Vec<String>, GeohashError> {
+    cover_rect(decode_mgrs(reference)?, len)
+}
@@ -0,0 +1,78 @@
+use geohash::{area_m2, decode_bbox, perimeter_m, total_area_m2, CellSet};
+
+const BASE32: &str = "0123456789bcdefghjkmnpqrstuvwxyz";
+
+// the area of a cell, summing thin bands of latitude
+fn integrated_area(hash: &str) -> f64 {
+    let rect = decode_bbox(hash).unwrap();
+    let (min, max) = (rect.min(), rect.max());
+    let r = 6_371_008.8f64;
+    let steps = 10_000;
+    let dy = (max.y - min.y).to_radians() / steps as f64;
+    let dx = (max.x - min.x).to_radians();
+    (0..steps)
+        .map(|i| {
+            let y = min.y.to_radians() + (i as f64 + 0.5) * dy;
+            r * r * y.cos() * dx * dy
+        })
+        .sum()
+}
+
+#[test]
+fn test_area_and_perimeter() {
+    for hash in [
+        "s00",
+        "u09",
+        "upb",
+        "zzz",
+        "000",
+        "7zz",
+        "u09tunq",
+        "s0000000000",
+    ] {
+        let area = area_m2(hash).unwrap();
+        let expected = integrated_area(hash);
+        assert!((area - expected).abs() <= 1e-6 * expected, "{}", hash);
+    }
+
+    // the children of a cell add up to it
+    let children: f64 = BASE32
+        .chars()
+        .map(|c| area_m2(&format!("u09{}", c)).unwrap())
+        .sum();
+    assert!((children - area_m2("u09").unwrap()).abs() < 1e-6 * children);
+
+    // cells shrink towards the poles, the meridian edges stay the same
+    let (equator, paris, polar) = (
+        perimeter_m("s00").unwrap(),
+        perimeter_m("u09").unwrap(),
+        perimeter_m("upb").unwrap(),
+    );
+    assert!(equator > paris && paris > polar);
+    let meridian = 2.0 * 6_371_008.8 * 1.40625f64.to_radians();
+    assert!(polar > meridian);
+    assert!(equator < 2.0 * meridian);
+
+    assert!(area_m2("").is_err());
+    assert!(perimeter_m("u0a").is_err());
+}
+
+#[test]
+fn test_total_area() {
+    let earth = 4.0 * std::f64::consts::PI * 6_371_008.8f64.powi(2);
+    let world: Vec<String> = BASE32.chars().map(String::from).collect();
+    assert!((total_area_m2(&world).unwrap() - earth).abs() < 1e-9 * earth);
+
+    let hashes = ["u09", "u09tun", "u0d", "u0d5", "gcp"];
+    let total = total_area_m2(hashes).unwrap();
+    let expected = ["u09", "u0d", "gcp"]
+        .iter()
+        .map(|hash| area_m2(hash).unwrap())
+        .sum::<f64>();
+    assert!((total - expected).abs() < 1e-6 * expected);
+    let set = CellSet::from_hashes(hashes).unwrap();
+    assert!((set.area_km2() * 1e6 - total).abs() < 1e-6 * total);
+
+    assert_eq!(total_area_m2(Vec::<String>::new()).unwrap(), 0.0);
+    assert!(total_area_m2(["u09", "a"]).is_err());
+}
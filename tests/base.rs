@@ -59,6 +59,16 @@ fn test_encode() {
         y: f64::NAN,
     };
     assert!(encode(c5, 4usize).is_err());
+
+    // the north and east edges of the world overflow into the first row and column
+    for (x, y, hash) in [
+        (180.0, 90.0, "000000000000"),
+        (180.0, 0.0, "800000000000"),
+        (0.0, 90.0, "h00000000000"),
+        (-180.0, -90.0, "000000000000"),
+    ] {
+        assert_eq!(encode(Coord { x, y }, 12).unwrap(), hash);
+    }
 }
 
 fn compare_within(a: f64, b: f64, diff: f64) {
@@ -0,0 +1,121 @@
+mod common;
+
+use common::next;
+use geohash::{decode, encode, CellSet, Coord};
+use std::collections::BTreeSet;
+
+const BASE32: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+// random cells of length 2 to 4 within a few cells of length 1, so that they overlap
+fn random_hashes(seed: &mut u64, n: usize) -> Vec<String> {
+    (0..n)
+        .map(|_| {
+            let len = 2 + (next(seed) * 3.0) as usize;
+            let mut hash = String::from(["u", "g", "s"][(next(seed) * 3.0) as usize]);
+            for _ in 1..len {
+                // few distinct second characters so that cells nest
+                let range = if hash.len() == 1 { 4.0 } else { 32.0 };
+                hash.push(BASE32[(next(seed) * range) as usize] as char);
+            }
+            hash
+        })
+        .collect()
+}
+
+// the cells of length 4 covered by the hashes
+fn expand(hashes: &[String]) -> BTreeSet<String> {
+    let mut cells = BTreeSet::new();
+    for hash in hashes {
+        let mut stack = vec![hash.clone()];
+        while let Some(cell) = stack.pop() {
+            if cell.len() == 4 {
+                cells.insert(cell);
+            } else {
+                stack.extend(BASE32.iter().map(|&c| format!("{}{}", cell, c as char)));
+            }
+        }
+    }
+    cells
+}
+
+fn expand_set(set: &CellSet) -> BTreeSet<String> {
+    expand(&set.cells())
+}
+
+#[test]
+fn test_set_operations() {
+    let mut seed = 47;
+    for _ in 0..20 {
+        let a_hashes = random_hashes(&mut seed, 40);
+        let b_hashes = random_hashes(&mut seed, 40);
+        let (a, b) = (
+            CellSet::from_hashes(&a_hashes).unwrap(),
+            CellSet::from_hashes(&b_hashes).unwrap(),
+        );
+        let (ea, eb) = (expand(&a_hashes), expand(&b_hashes));
+        assert_eq!(expand_set(&a), ea);
+
+        assert_eq!(expand_set(&a.union(&b)), &ea | &eb);
+        assert_eq!(expand_set(&a.intersection(&b)), &ea & &eb);
+        assert_eq!(expand_set(&a.difference(&b)), &ea - &eb);
+        assert_eq!(expand_set(&a.symmetric_difference(&b)), &ea ^ &eb);
+        assert_eq!(a.union(&b), b.union(&a));
+        assert!(a.difference(&a).is_empty());
+
+        // the cells are compact: no cell overlaps another, and no 32 siblings are left
+        let cells = a.cells();
+        for w in cells.windows(2) {
+            assert!(w[0] < w[1] && !w[1].starts_with(w[0].as_str()));
+        }
+        for cell in &cells {
+            let parent = &cell[..cell.len() - 1];
+            let siblings = cells
+                .iter()
+                .filter(|c| c.len() == cell.len() && c.starts_with(parent));
+            assert!(cell.len() == 1 || siblings.count() < 32);
+        }
+
+        // inserting the hashes one by one gives the same set
+        let mut inserted = CellSet::new();
+        for hash in &a_hashes {
+            inserted.insert(hash).unwrap();
+        }
+        assert_eq!(inserted, a);
+    }
+    assert!(CellSet::from_hashes(["u09", "a"]).is_err());
+    assert!(CellSet::new().insert("").is_err());
+}
+
+#[test]
+fn test_contains_point_and_area() {
+    let mut seed = 53;
+    let hashes = random_hashes(&mut seed, 60);
+    let set = CellSet::from_hashes(&hashes).unwrap();
+    let cells = expand(&hashes);
+    for _ in 0..5000 {
+        let c = Coord {
+            x: next(&mut seed) * 90.0 - 45.0,
+            y: next(&mut seed) * 90.0,
+        };
+        let inside = cells.contains(&encode(c, 4).unwrap());
+        assert_eq!(set.contains_point(c), inside);
+    }
+    for cell in &cells {
+        assert!(set.contains_point(decode(cell).unwrap().0));
+    }
+    assert!(!set.contains_point(Coord { x: 200.0, y: 0.0 }));
+
+    // the whole world, and the northern hemisphere from the top two rows of cells
+    let all: Vec<String> = BASE32.iter().map(|&c| (c as char).to_string()).collect();
+    let earth = 4.0 * std::f64::consts::PI * 6371.0088f64.powi(2);
+    let world = CellSet::from_hashes(&all).unwrap();
+    assert!((world.area_km2() - earth).abs() < 1e-6 * earth);
+    let north = world.intersection(
+        &CellSet::from_hashes([
+            "8", "9", "d", "e", "s", "t", "w", "x", "b", "c", "f", "g", "u", "v", "y", "z",
+        ])
+        .unwrap(),
+    );
+    assert!((north.area_km2() - earth / 2.0).abs() < 1e-6 * earth);
+    assert_eq!(CellSet::new().area_km2(), 0.0);
+}
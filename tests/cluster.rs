@@ -0,0 +1,188 @@
+mod common;
+
+use common::next;
+use geohash::{cluster_points, encode, eps_precision, neighbors, Coord};
+
+fn blob(seed: &mut u64, n: usize, center: Coord<f64>, spread: f64) -> Vec<Coord<f64>> {
+    (0..n)
+        .map(|_| Coord {
+            x: center.x + (next(seed) * 2.0 - 1.0) * spread,
+            y: center.y + (next(seed) * 2.0 - 1.0) * spread,
+        })
+        .collect()
+}
+
+#[test]
+fn test_cluster_points() {
+    let mut seed = 17;
+    let centers = [
+        Coord { x: 2.35, y: 48.85 },
+        Coord { x: 2.50, y: 48.85 },
+        Coord {
+            x: -73.98,
+            y: 40.75,
+        },
+    ];
+    let mut points = Vec::new();
+    for &center in &centers {
+        points.extend(blob(&mut seed, 500, center, 0.02));
+    }
+    // lone points far from everything else
+    let noise = [Coord { x: 100.0, y: 10.0 }, Coord { x: -20.0, y: -40.0 }];
+    points.extend(noise);
+
+    let clustering = cluster_points(points.iter().copied(), 5, 5).unwrap();
+    assert_eq!(clustering.labels.len(), points.len());
+    assert_eq!(clustering.clusters.len(), 3);
+
+    // every blob is a single cluster of its own
+    let mut seen = Vec::new();
+    for blob in clustering.labels[..1500].chunks(500) {
+        let label = blob[0].unwrap();
+        assert!(blob.iter().all(|&l| l == Some(label)));
+        assert!(!seen.contains(&label));
+        seen.push(label);
+        assert_eq!(clustering.clusters[label].count, 500);
+    }
+    assert_eq!(clustering.labels[1500..], [None, None]);
+
+    // the cells of each cluster are those of its points
+    for (id, cluster) in clustering.clusters.iter().enumerate() {
+        let mut cells: Vec<String> = points
+            .iter()
+            .zip(&clustering.labels)
+            .filter(|(_, &l)| l == Some(id))
+            .map(|(&c, _)| encode(c, 5).unwrap())
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        assert_eq!(cluster.cells, cells);
+    }
+
+    // at a coarser precision the two blobs in Paris touch
+    let clustering = cluster_points(points.iter().copied(), 4, 5).unwrap();
+    assert_eq!(clustering.clusters.len(), 2);
+    assert_eq!(clustering.labels[0], clustering.labels[500]);
+}
+
+#[test]
+fn test_sparse_cells() {
+    let dense = encode(
+        Coord {
+            x: 179.99,
+            y: -16.5,
+        },
+        6,
+    )
+    .unwrap();
+    let (center, _, _) = geohash::decode(&dense).unwrap();
+    let mut points = vec![center; 3];
+
+    // a single point across the antimeridian is attached to the dense cell
+    let across = neighbors(&dense).unwrap().e;
+    let (c, _, _) = geohash::decode(&across).unwrap();
+    assert!(c.x < 0.0);
+    points.push(c);
+
+    // and a single point two cells away is noise
+    let far = neighbors(&across).unwrap().e;
+    points.push(geohash::decode(&far).unwrap().0);
+
+    let clustering = cluster_points(points, 6, 3).unwrap();
+    assert_eq!(
+        clustering.labels,
+        [Some(0), Some(0), Some(0), Some(0), None]
+    );
+    let mut cells = vec![across, dense.clone()];
+    cells.sort_unstable();
+    assert_eq!(clustering.clusters[0].cells, cells);
+    assert_eq!(clustering.clusters[0].count, 4);
+
+    // two dense cells only connected through a sparse cell stay apart, and the sparse cell
+    // goes to the first of them by geohash
+    assert!(far < dense);
+    let f = geohash::decode(&far).unwrap().0;
+    let clustering = cluster_points([center, center, c, f, f], 6, 2).unwrap();
+    assert_eq!(clustering.clusters.len(), 2);
+    assert_eq!(
+        clustering.labels,
+        [Some(1), Some(1), Some(0), Some(0), Some(0)]
+    );
+    assert!(cluster_points([center], 0, 1).is_err());
+    assert!(cluster_points([center], 13, 1).is_err());
+}
+
+#[test]
+fn test_eps_precision() {
+    let mut last = Some(12);
+    for meters in [
+        0.01,
+        1.0,
+        10.0,
+        100.0,
+        1000.0,
+        10_000.0,
+        100_000.0,
+        1_000_000.0,
+    ] {
+        let len = eps_precision(meters, 45.0);
+        assert!(len <= last);
+        last = len;
+        if let Some(len) = len {
+            let (_, height, width) = geohash::decode(&"0".repeat(len)).unwrap();
+            assert!(height * 2.0 * 111_194.9 >= meters);
+            assert!(width * 2.0 * 111_194.9 * 45f64.to_radians().cos() >= meters);
+            // cells shrink towards the poles
+            assert!(eps_precision(meters, 80.0) <= Some(len));
+        }
+    }
+    assert_eq!(eps_precision(0.01, 0.0), Some(12));
+    assert_eq!(eps_precision(30_000_000.0, 0.0), None);
+}
+
+#[test]
+fn test_outline() {
+    // a dense block of 3 by 3 cells, whose outline is all but its center
+    let center = "u09tun";
+    let around = neighbors(center).unwrap();
+    let mut block = vec![
+        center.to_string(),
+        around.n,
+        around.ne,
+        around.e,
+        around.se,
+        around.s,
+        around.sw,
+        around.w,
+        around.nw,
+    ];
+    let points: Vec<Coord<f64>> = block
+        .iter()
+        .flat_map(|hash| vec![geohash::decode(hash).unwrap().0; 2])
+        .collect();
+    let clustering = cluster_points(points, 6, 2).unwrap();
+    assert_eq!(clustering.clusters.len(), 1);
+    block.sort_unstable();
+    assert_eq!(clustering.clusters[0].cells, block);
+    block.retain(|hash| hash != center);
+    assert_eq!(clustering.clusters[0].outline, block);
+}
+
+#[test]
+fn test_poles() {
+    // cells at opposite poles are not next to each other
+    let points = [
+        Coord { x: 10.0, y: 89.99 },
+        Coord { x: 10.0, y: 89.98 },
+        Coord { x: 10.0, y: -89.99 },
+        Coord { x: 10.0, y: -89.98 },
+    ];
+    let clustering = cluster_points(points, 3, 2).unwrap();
+    assert_eq!(clustering.clusters.len(), 2);
+    assert_eq!(clustering.labels, [Some(1), Some(1), Some(0), Some(0)]);
+    assert_eq!(clustering.clusters[0].cells, ["h0p"]);
+    assert_eq!(clustering.clusters[1].cells, ["upz"]);
+    // cells along the poles are on the outline
+    assert_eq!(clustering.clusters[0].outline, ["h0p"]);
+    assert_eq!(clustering.clusters[1].count, 2);
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use common::next;
+use geohash::{
+    decode_bbox, decode_bbox_int, encode, encode_int, neighbors, Codec, Coord, Direction,
+};
+
+#[test]
+fn test_binary_codecs() {
+    let mut seed = 29;
+    for _ in 0..1000 {
+        let c = Coord {
+            x: next(&mut seed) * 360.0 - 180.0,
+            y: next(&mut seed) * 180.0 - 90.0,
+        };
+        for len in [1, 5, 12] {
+            let hash = encode(c, len).unwrap();
+            assert_eq!(Codec::GEOHASH.encode(c, len).unwrap(), hash);
+            assert_eq!(
+                Codec::GEOHASH.decode_bbox(&hash).unwrap(),
+                decode_bbox(&hash).unwrap()
+            );
+        }
+        for (codec, bits) in [(Codec::BASE4, 2), (Codec::BASE16, 4)] {
+            for len in [1, 7, codec.max_len()] {
+                let total = bits * len as u32;
+                let int_hash = encode_int(c, total).unwrap();
+                let hash = codec.encode(c, len).unwrap();
+                let radix = 1 << bits;
+                assert_eq!(u64::from_str_radix(&hash, radix).unwrap(), int_hash);
+                assert_eq!(
+                    codec.decode_bbox(&hash).unwrap(),
+                    decode_bbox_int(int_hash, total).unwrap()
+                );
+            }
+        }
+    }
+    assert_eq!(Codec::BASE4.max_len(), 32);
+    assert_eq!(Codec::BASE16.max_len(), 16);
+
+    // neighbors agree with the classic geohash
+    let hash = "9q60y60rhs";
+    assert_eq!(
+        Codec::GEOHASH.neighbors(hash).unwrap(),
+        neighbors(hash).unwrap()
+    );
+    for edge in ["b", "zz", "0", "pbp"] {
+        assert_eq!(
+            Codec::GEOHASH.neighbors(edge).unwrap(),
+            neighbors(edge).unwrap()
+        );
+    }
+
+    // base 4 keeps a longitude and a latitude bit per character
+    let east = Codec::BASE4.neighbor("0333", Direction::E).unwrap();
+    assert_eq!(east, "2111");
+    assert!(Codec::BASE4.decode_bbox("0124").is_err());
+    // the error names the limit of the codec
+    assert_eq!(
+        Codec::BASE16.encode(c_zero(), 17).unwrap_err().to_string(),
+        "Invalid codec string: length 17, accepted values are between 1 and 16, inclusive"
+    );
+    assert!(Codec::BASE4.encode(c_zero(), 32).is_ok());
+    assert!(Codec::BASE16.decode_bbox("").is_err());
+}
+
+fn c_zero() -> Coord<f64> {
+    Coord { x: 0.0, y: 0.0 }
+}
+
+#[test]
+fn test_world_edges() {
+    // the free functions go through the base32 codec, and keep their hashes on the north and
+    // east edges of the world, unlike integer geohashes which clamp them
+    for (c, hash) in [
+        (Coord { x: 180.0, y: 90.0 }, "000000000000"),
+        (Coord { x: 180.0, y: 0.0 }, "800000000000"),
+        (Coord { x: 0.0, y: 90.0 }, "h00000000000"),
+        (
+            Coord {
+                x: -180.0,
+                y: -90.0,
+            },
+            "000000000000",
+        ),
+    ] {
+        assert_eq!(encode(c, 12).unwrap(), hash);
+        assert_eq!(Codec::GEOHASH.encode(c, 12).unwrap(), hash);
+        assert_eq!(geohash::encode_iter(c).unwrap().collect::<String>(), hash);
+        assert_eq!(encode_int(c, 60).unwrap() >> 55, encode_int(c, 5).unwrap());
+        assert_eq!(
+            decode_bbox(hash).unwrap(),
+            Codec::GEOHASH.decode_bbox(hash).unwrap()
+        );
+    }
+    assert!(matches!(
+        decode_bbox("u0a"),
+        Err(geohash::GeohashError::InvalidHashCharacter('a'))
+    ));
+    assert!(decode_bbox("u09tunquc3fr0").is_err());
+}
+
+#[test]
+fn test_geohash_36() {
+    let codec = Codec::GEOHASH_36;
+    assert_eq!(codec.max_len(), 15);
+
+    // London Bridge, the example of the original description of Geohash-36
+    let c = Coord {
+        x: -0.086666,
+        y: 51.504444,
+    };
+    assert_eq!(codec.encode(c, 10).unwrap(), "bdrdC26BqH");
+    // the first character picks one of 6 by 6 cells of 60 by 30 degrees, from the north-west
+    assert_eq!(codec.encode(Coord { x: -179.0, y: 89.0 }, 1).unwrap(), "2");
+    assert_eq!(codec.encode(Coord { x: 179.0, y: 89.0 }, 1).unwrap(), "7");
+    assert_eq!(
+        codec
+            .encode(
+                Coord {
+                    x: -179.0,
+                    y: -89.0
+                },
+                1
+            )
+            .unwrap(),
+        "R"
+    );
+    assert_eq!(codec.encode(Coord { x: 180.0, y: -90.0 }, 1).unwrap(), "X");
+    assert!(codec.decode_bbox("bdrdc26BqH").is_err());
+
+    let mut seed = 31;
+    for _ in 0..1000 {
+        let c = Coord {
+            x: next(&mut seed) * 360.0 - 180.0,
+            y: next(&mut seed) * 180.0 - 90.0,
+        };
+        for len in [1, 4, 9, 15] {
+            let hash = codec.encode(c, len).unwrap();
+            let (center, lon_err, lat_err) = codec.decode(&hash).unwrap();
+            assert!((center.x - c.x).abs() <= lon_err && (center.y - c.y).abs() <= lat_err);
+            assert!((lat_err * 2.0 - 180.0 / 6f64.powi(len as i32)).abs() < 1e-12);
+            assert_eq!(codec.encode(center, len).unwrap(), hash);
+
+            // neighbors share an edge or a corner
+            let cell = codec.decode_bbox(&hash).unwrap();
+            let n = codec
+                .decode_bbox(&codec.neighbor(&hash, Direction::N).unwrap())
+                .unwrap();
+            if cell.max().y < 90.0 {
+                assert!((n.min().y - cell.max().y).abs() < 1e-9);
+                assert!((n.min().x - cell.min().x).abs() < 1e-9);
+            }
+            let e = codec
+                .decode_bbox(&codec.neighbor(&hash, Direction::E).unwrap())
+                .unwrap();
+            if cell.max().x < 180.0 {
+                assert!((e.min().x - cell.max().x).abs() < 1e-9);
+            } else {
+                assert_eq!(e.min().x, -180.0);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_custom_codecs() {
+    // a binary codec with an upper case alphabet
+    let upper = Codec::binary("0123456789BCDEFGHJKMNPQRSTUVWXYZ").unwrap();
+    let c = Coord {
+        x: -120.6623,
+        y: 35.3003,
+    };
+    assert_eq!(upper.encode(c, 10).unwrap(), "9Q60Y60RHS");
+    assert_eq!(
+        upper.decode_bbox("9Q60Y").unwrap(),
+        decode_bbox("9q60y").unwrap()
+    );
+
+    // a grid of 3 by 3
+    let nine = Codec::grid("abcdefghi", 3, 3).unwrap();
+    assert_eq!(nine.encode(Coord { x: 0.0, y: 0.0 }, 1).unwrap(), "e");
+    assert_eq!(nine.neighbor("e", Direction::NW).unwrap(), "a");
+    assert_eq!(nine.neighbor("a", Direction::W).unwrap(), "c");
+    assert_eq!(nine.alphabet(), "abcdefghi");
+
+    assert!(Codec::binary("012").is_err());
+    assert!(Codec::binary("0012").is_err());
+    assert!(Codec::grid("abcdef", 2, 2).is_err());
+    assert!(Codec::grid("abcdé", 1, 5).is_err());
+}
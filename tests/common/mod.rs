@@ -0,0 +1,21 @@
+// helpers shared by the integration tests, which each use only some of them
+#![allow(dead_code)]
+
+use geohash::Coord;
+
+/// The next float in `[0, 1)` of a small deterministic generator, so that the test points are
+/// the same on every run.
+pub fn next(seed: &mut u64) -> f64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*seed >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Great-circle distance in meters, with the same mean earth radius as the crate.
+pub fn haversine(a: Coord<f64>, b: Coord<f64>) -> f64 {
+    let (lat_a, lat_b) = (a.y.to_radians(), b.y.to_radians());
+    let h = ((lat_b - lat_a) / 2.0).sin().powi(2)
+        + lat_a.cos() * lat_b.cos() * ((b.x - a.x).to_radians() / 2.0).sin().powi(2);
+    2.0 * 6_371_008.8 * h.sqrt().min(1.0).asin()
+}
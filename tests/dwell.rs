@@ -0,0 +1,187 @@
+mod common;
+
+use common::next;
+use geohash::{decode_bbox, encode, Coord, DwellDetector, DwellEvent};
+
+fn run(detector: &mut DwellDetector, samples: &[(u64, Coord<f64>)]) -> Vec<DwellEvent> {
+    let mut events = Vec::new();
+    for &(t, c) in samples {
+        events.extend(detector.push(t, c).unwrap());
+    }
+    events.extend(detector.finish());
+    events
+}
+
+#[test]
+fn test_runs_of_cells() {
+    // without hysteresis, stops are the runs of samples in the same cell
+    let mut seed = 79;
+    for _ in 0..50 {
+        let mut samples = Vec::new();
+        let mut t = 0;
+        for _ in 0..200 {
+            let c = Coord {
+                x: 10.0 + next(&mut seed) * 0.05,
+                y: 50.0 + next(&mut seed) * 0.05,
+            };
+            // stay for a while, with a few samples per stop
+            for _ in 0..(next(&mut seed) * 6.0) as usize {
+                t += (next(&mut seed) * 120.0) as u64;
+                samples.push((t, c));
+            }
+        }
+
+        let mut expected = Vec::new();
+        let mut i = 0;
+        while i < samples.len() {
+            let hash = encode(samples[i].1, 6).unwrap();
+            let mut j = i;
+            while j + 1 < samples.len() && encode(samples[j + 1].1, 6).unwrap() == hash {
+                j += 1;
+            }
+            if samples[j].0 - samples[i].0 >= 300 {
+                expected.push(DwellEvent {
+                    geohash: hash,
+                    enter: samples[i].0,
+                    exit: samples[j].0,
+                    count: j - i + 1,
+                });
+            }
+            i = j + 1;
+        }
+
+        let mut detector = DwellDetector::new(6, 300).unwrap();
+        assert_eq!(run(&mut detector, &samples), expected);
+    }
+}
+
+#[test]
+fn test_jitter() {
+    // a parked vehicle right at the border of two cells
+    let cell = decode_bbox("u09tunq").unwrap();
+    let border = cell.max().x;
+    let mut samples = Vec::new();
+    let mut seed = 83;
+    for t in 0..100 {
+        let offset = (next(&mut seed) - 0.7) * 2e-5;
+        samples.push((
+            t * 30,
+            Coord {
+                x: border + offset,
+                y: cell.center().y,
+            },
+        ));
+    }
+    let mut inside = 0;
+    let mut longest = 0;
+    for &(_, c) in samples.iter() {
+        if encode(c, 7).unwrap() == "u09tunq" {
+            inside += 1;
+            longest = longest.max(inside);
+        } else {
+            inside = 0;
+        }
+    }
+    assert!(longest < 20);
+
+    // the stop is split into short visits
+    let mut detector = DwellDetector::new(7, 600).unwrap();
+    assert!(run(&mut detector, &samples).is_empty());
+
+    // hysteresis ignores the short trips across the border
+    let mut detector = DwellDetector::new(7, 600).unwrap().with_hysteresis(20);
+    let events = run(&mut detector, &samples);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].geohash, encode(samples[0].1, 7).unwrap());
+    assert_eq!(events[0].enter, 0);
+    assert!(events[0].exit >= 2700);
+
+    // and so do neighbors, which hold the whole stop
+    let mut detector = DwellDetector::new(7, 600).unwrap().with_neighbors(true);
+    let events = run(&mut detector, &samples);
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        (events[0].enter, events[0].exit, events[0].count),
+        (0, 2970, 100)
+    );
+}
+
+#[test]
+fn test_leaving() {
+    let depot = Coord {
+        x: 2.2945,
+        y: 48.8582,
+    };
+    let road = Coord {
+        x: 2.3100,
+        y: 48.8600,
+    };
+    let shop = Coord {
+        x: 2.3500,
+        y: 48.8600,
+    };
+    let mut detector = DwellDetector::new(7, 300).unwrap().with_hysteresis(3);
+
+    let mut samples = Vec::new();
+    for t in 0..10 {
+        samples.push((t * 60, depot));
+    }
+    // too few samples to end the stop, which goes on
+    samples.push((600, road));
+    samples.push((660, road));
+    samples.push((720, depot));
+    // a short drive to the shop, ending the first stop
+    samples.push((780, road));
+    for t in 14..30 {
+        samples.push((t * 60, shop));
+    }
+    let events = run(&mut detector, &samples);
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        (events[0].enter, events[0].exit, events[0].count),
+        (0, 720, 13)
+    );
+    // the single sample on the road is too short to be a stop
+    assert_eq!(events[1].geohash, encode(shop, 7).unwrap());
+    assert_eq!(
+        (events[1].enter, events[1].exit, events[1].count),
+        (840, 1740, 16)
+    );
+
+    assert!(DwellDetector::new(0, 300).is_err());
+    assert!(DwellDetector::new(13, 300).is_err());
+    let mut detector = DwellDetector::new(7, 300).unwrap();
+    detector.push(100, depot).unwrap();
+    assert!(detector.push(99, depot).is_err());
+    assert!(detector.push(100, Coord { x: 0.0, y: 95.0 }).is_err());
+}
+
+#[test]
+fn test_poles() {
+    // the cells around a cell along the north pole don't wrap over to the south pole
+    let north = Coord { x: 10.0, y: 89.99 };
+    let east = Coord { x: 11.5, y: 89.99 };
+    let south = Coord { x: 10.0, y: -89.99 };
+    let mut detector = DwellDetector::new(3, 60).unwrap().with_neighbors(true);
+    let events = run(
+        &mut detector,
+        &[(0, north), (60, east), (120, south), (180, south)],
+    );
+    assert_eq!(
+        events,
+        [
+            DwellEvent {
+                geohash: encode(north, 3).unwrap(),
+                enter: 0,
+                exit: 60,
+                count: 2,
+            },
+            DwellEvent {
+                geohash: encode(south, 3).unwrap(),
+                enter: 120,
+                exit: 180,
+                count: 2,
+            },
+        ]
+    );
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::next;
+use geo_types::{line_string, point, polygon, Geometry, GeometryCollection, LineString};
+use geohash::{cover_rect, decode_bbox, enclosing_cells, enclosing_hash, encode, Coord, Rect};
+
+fn random_rect(seed: &mut u64) -> Rect<f64> {
+    let size = 10f64.powf(-6.0 + next(seed) * 8.0);
+    let x = next(seed) * (360.0 - size) - 180.0;
+    let y = next(seed) * (180.0 - size) - 90.0;
+    Rect::new(
+        Coord { x, y },
+        Coord {
+            x: x + size * next(seed),
+            y: y + size * next(seed),
+        },
+    )
+}
+
+#[test]
+fn test_enclosing_hash() {
+    let mut seed = 61;
+    for _ in 0..5000 {
+        let rect = random_rect(&mut seed);
+        match enclosing_hash(rect).unwrap() {
+            Some(hash) => {
+                let bbox = decode_bbox(&hash).unwrap();
+                assert!(bbox.min().x <= rect.min().x && rect.max().x <= bbox.max().x);
+                assert!(bbox.min().y <= rect.min().y && rect.max().y <= bbox.max().y);
+                // the cell is the single cell covering the rectangle at its length, and the
+                // cells one character longer are more than one
+                assert_eq!(cover_rect(rect, hash.len()).unwrap(), vec![hash.clone()]);
+                if hash.len() < 12 {
+                    assert!(cover_rect(rect, hash.len() + 1).unwrap().len() > 1);
+                }
+            }
+            None => assert!(cover_rect(rect, 1).unwrap().len() > 1),
+        }
+    }
+
+    // cells enclose themselves, even along the edges of the world
+    for hash in ["u09tunq", "zzz", "000", "pbp", "b", "s00000000000"] {
+        let rect = decode_bbox(hash).unwrap();
+        assert_eq!(enclosing_hash(rect).unwrap().as_deref(), Some(hash));
+    }
+    assert!(enclosing_hash(Rect::new(
+        Coord { x: 0.0, y: 0.0 },
+        Coord { x: 181.0, y: 0.0 }
+    ))
+    .is_err());
+}
+
+#[test]
+fn test_enclosing_cells() {
+    let mut seed = 67;
+    for _ in 0..2000 {
+        let rect = random_rect(&mut seed);
+        for max in [1, 2, 4, 9] {
+            let cells = enclosing_cells(rect, max).unwrap();
+            assert!(cells.len() <= max);
+            if cells.is_empty() {
+                assert!(cover_rect(rect, 1).unwrap().len() > max);
+                continue;
+            }
+            let len = cells[0].len();
+            assert_eq!(cover_rect(rect, len).unwrap(), cells);
+            if len < 12 {
+                assert!(cover_rect(rect, len + 1).unwrap().len() > max);
+            }
+        }
+        assert_eq!(
+            enclosing_cells(rect, 1).unwrap(),
+            enclosing_hash(rect)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn test_enclosing_geometries() {
+    let triangle = polygon![(x: 2.29, y: 48.85), (x: 2.30, y: 48.85), (x: 2.295, y: 48.86)];
+    assert_eq!(
+        enclosing_hash(triangle.clone()).unwrap().as_deref(),
+        Some("u09tu")
+    );
+
+    let collection = GeometryCollection::from(vec![
+        Geometry::from(point!(x: 2.2945, y: 48.8582)),
+        Geometry::from(line_string![(x: 2.29, y: 48.85), (x: 2.30, y: 48.86)]),
+    ]);
+    assert_eq!(
+        enclosing_hash(Geometry::GeometryCollection(collection))
+            .unwrap()
+            .as_deref(),
+        Some("u09tu")
+    );
+
+    // a point is enclosed by its full geohash
+    let hash = enclosing_hash(point!(x: 2.2945, y: 48.8582)).unwrap();
+    assert_eq!(
+        hash,
+        Some(
+            encode(
+                Coord {
+                    x: 2.2945,
+                    y: 48.8582
+                },
+                12
+            )
+            .unwrap()
+        )
+    );
+
+    // empty geometries have no enclosing cells
+    assert_eq!(
+        enclosing_hash(LineString::<f64>::new(vec![])).unwrap(),
+        None
+    );
+    assert!(enclosing_cells(LineString::<f64>::new(vec![]), 4)
+        .unwrap()
+        .is_empty());
+    assert!(enclosing_hash(point!(x: 0.0, y: 91.0)).is_err());
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::next;
+use geo_types::{polygon, LineString, Polygon};
+use geohash::{Coord, GeofenceSet};
+
+fn contains(polygon: &Polygon<f64>, c: Coord<f64>) -> bool {
+    let mut inside = false;
+    for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+        for line in ring.lines() {
+            let (a, b) = (line.start, line.end);
+            if (a.y > c.y) != (b.y > c.y) && c.x < a.x + (c.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+fn fences() -> Vec<(u32, Polygon<f64>)> {
+    // a star shaped polygon
+    let star: Vec<(f64, f64)> = (0..10)
+        .map(|i| {
+            let angle = i as f64 * std::f64::consts::PI / 5.0;
+            let r = if i % 2 == 0 { 1.0 } else { 0.4 };
+            (10.0 + r * angle.cos(), 50.0 + r * angle.sin())
+        })
+        .collect();
+    vec![
+        (1, Polygon::new(LineString::from(star), vec![])),
+        (
+            2,
+            polygon!(
+                exterior: [(x: 9.0, y: 49.0), (x: 11.0, y: 49.0), (x: 11.0, y: 51.0), (x: 9.0, y: 51.0)],
+                interiors: [[(x: 9.5, y: 49.5), (x: 10.5, y: 49.5), (x: 10.5, y: 50.5), (x: 9.5, y: 50.5)]],
+            ),
+        ),
+        (
+            3,
+            polygon![(x: 9.9, y: 49.9), (x: 10.01, y: 49.9), (x: 10.01, y: 50.01), (x: 9.9, y: 50.01)],
+        ),
+        (
+            4,
+            polygon![(x: -170.0, y: -80.0), (x: 170.0, y: -80.0), (x: 0.0, y: 80.0)],
+        ),
+    ]
+}
+
+#[test]
+fn test_geofence_lookup_matches_exact() {
+    let mut set = GeofenceSet::new();
+    for (id, polygon) in fences() {
+        assert!(set.insert(id, polygon).unwrap().is_none());
+    }
+    assert_eq!(set.len(), 4);
+
+    let mut seed = 3;
+    for i in 0..20000 {
+        // most points near the fences, some anywhere
+        let c = if i % 10 == 0 {
+            Coord {
+                x: next(&mut seed) * 360.0 - 180.0,
+                y: next(&mut seed) * 180.0 - 90.0,
+            }
+        } else {
+            Coord {
+                x: 8.5 + next(&mut seed) * 3.0,
+                y: 48.5 + next(&mut seed) * 3.0,
+            }
+        };
+        let expected: Vec<u32> = fences()
+            .into_iter()
+            .filter(|(_, p)| contains(p, c))
+            .map(|(id, _)| id)
+            .collect();
+        let found: Vec<u32> = set.lookup(c).into_iter().copied().collect();
+        assert_eq!(found, expected, "{:?}", c);
+    }
+}
+
+#[test]
+fn test_geofence_add_remove() {
+    let mut set = GeofenceSet::with_refinement(1);
+    let c = Coord { x: 10.0, y: 50.0 };
+    for (id, polygon) in fences() {
+        set.insert(id, polygon).unwrap();
+    }
+    assert_eq!(set.lookup(c), [&1, &3, &4]);
+
+    let star = set.remove(&1).unwrap();
+    assert_eq!(set.lookup(c), [&3, &4]);
+    assert!(set.remove(&1).is_none());
+
+    // replacing a fence drops its old cells
+    let old = set.insert(3, star).unwrap();
+    assert!(old.is_some());
+    assert_eq!(set.lookup(c), [&3, &4]);
+    assert_eq!(set.lookup(Coord { x: 9.95, y: 49.95 }), [&3, &4]);
+    assert_eq!(set.lookup(Coord { x: 9.2, y: 49.2 }), [&2, &4]);
+
+    for id in 1..=4 {
+        set.remove(&id);
+    }
+    assert!(set.is_empty());
+    assert!(set.lookup(c).is_empty());
+
+    let invalid = polygon![(x: 0.0, y: 0.0), (x: 200.0, y: 0.0), (x: 0.0, y: 1.0)];
+    assert!(set.insert(5, invalid).is_err());
+    assert!(set.lookup(Coord { x: 200.0, y: 0.0 }).is_empty());
+
+    // the coordinates of the holes are checked too
+    let invalid_hole = Polygon::new(
+        LineString::from(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]),
+        vec![LineString::from(vec![(1.0, 1.0), (1.0, 95.0), (2.0, 1.0)])],
+    );
+    assert!(set.insert(6, invalid_hole).is_err());
+    assert!(set.is_empty());
+}
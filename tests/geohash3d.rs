@@ -0,0 +1,202 @@
+mod common;
+
+use common::next;
+use geohash::{decode_bbox_int, encode_int, Coord, Direction, Geohash3D};
+
+// encodes by bisecting the three ranges in turn
+fn bisect(c: Coord<f64>, alt: f64, min_alt: f64, max_alt: f64, bits: u32) -> u64 {
+    let mut ranges = [(-180.0, 180.0), (-90.0, 90.0), (min_alt, max_alt)];
+    let values = [c.x, c.y, alt];
+    let mut int_hash = 0;
+    for i in 0..bits as usize {
+        let (lo, hi) = ranges[i % 3];
+        let mid = (lo + hi) / 2.0;
+        int_hash <<= 1;
+        if values[i % 3] >= mid {
+            int_hash |= 1;
+            ranges[i % 3].0 = mid;
+        } else {
+            ranges[i % 3].1 = mid;
+        }
+    }
+    int_hash
+}
+
+#[test]
+fn test_encode_decode() {
+    let airspace = Geohash3D::new(-500.0, 20_000.0).unwrap();
+    assert_eq!(airspace, Geohash3D::default());
+    let mut seed = 37;
+    for _ in 0..2000 {
+        let c = Coord {
+            x: next(&mut seed) * 359.0 - 179.5,
+            y: next(&mut seed) * 179.0 - 89.5,
+        };
+        let alt = next(&mut seed) * 20_500.0 - 500.0;
+        for bits in [1, 2, 3, 10, 31, 45, 63] {
+            let int_hash = airspace.encode_int(c, alt, bits).unwrap();
+            assert_eq!(
+                int_hash,
+                bisect(c, alt, -500.0, 20_000.0, bits),
+                "{:?} {}",
+                c,
+                alt
+            );
+            let bbox = airspace.decode_bbox_int(int_hash, bits).unwrap();
+            assert!(bbox.contains(c, alt));
+
+            // the horizontal grid is the one of 2D geohashes
+            let horizontal = bits - bits / 3;
+            assert_eq!(
+                bbox.rect,
+                decode_bbox_int(encode_int(c, horizontal).unwrap(), horizontal).unwrap()
+            );
+        }
+        for len in [1, 6, 12] {
+            let hash = airspace.encode(c, alt, len).unwrap();
+            let bbox = airspace.decode_bbox(&hash).unwrap();
+            assert!(bbox.contains(c, alt));
+            let (center, center_alt) = bbox.center();
+            assert_eq!(airspace.encode(center, center_alt, len).unwrap(), hash);
+        }
+    }
+
+    // the bounds of the range are in the first and last cells
+    assert_eq!(
+        airspace
+            .encode_int(Coord { x: 0.0, y: 0.0 }, -500.0, 3)
+            .unwrap(),
+        0b110
+    );
+    assert_eq!(
+        airspace
+            .encode_int(Coord { x: 0.0, y: 0.0 }, 20_000.0, 3)
+            .unwrap(),
+        0b111
+    );
+
+    let c = Coord { x: 0.0, y: 0.0 };
+    assert!(airspace.encode(c, 20_000.1, 5).is_err());
+    assert!(airspace.encode(c, -501.0, 5).is_err());
+    assert!(airspace.encode(c, f64::NAN, 5).is_err());
+    assert!(airspace.encode(Coord { x: 0.0, y: 91.0 }, 0.0, 5).is_err());
+    assert!(airspace.encode(c, 0.0, 13).is_err());
+    assert!(airspace.encode_int(c, 0.0, 64).is_err());
+    assert!(airspace.decode_bbox("8dn7a").is_err());
+    assert!(Geohash3D::new(10.0, 10.0).is_err());
+    assert!(Geohash3D::new(0.0, f64::INFINITY).is_err());
+}
+
+#[test]
+fn test_neighbors() {
+    let airspace = Geohash3D::new(0.0, 12_000.0).unwrap();
+    let mut seed = 41;
+    for _ in 0..500 {
+        // away from the edges of the world, where neighbors wrap around
+        let c = Coord {
+            x: next(&mut seed) * 300.0 - 150.0,
+            y: next(&mut seed) * 120.0 - 60.0,
+        };
+        let alt = next(&mut seed) * 12_000.0;
+        for len in [2, 5, 9] {
+            let hash = airspace.encode(c, alt, len).unwrap();
+            let bbox = airspace.decode_bbox(&hash).unwrap();
+            let neighbors = airspace.neighbors(&hash).unwrap();
+
+            let top = bbox.max_alt == 12_000.0;
+            let bottom = bbox.min_alt == 0.0;
+            let expected = 26 - 9 * (top as usize + bottom as usize);
+            assert_eq!(neighbors.len(), expected, "{}", hash);
+            assert!(!neighbors.contains(&hash));
+
+            // every neighbor touches the cell
+            for neighbor in neighbors.iter() {
+                let other = airspace.decode_bbox(neighbor).unwrap();
+                let eps = 1e-9;
+                let touches = |a0: f64, a1: f64, b0: f64, b1: f64| {
+                    (a0 - b1).abs() < eps || (b0 - a1).abs() < eps || (a0 - b0).abs() < eps
+                };
+                assert!(touches(
+                    bbox.rect.min().x,
+                    bbox.rect.max().x,
+                    other.rect.min().x,
+                    other.rect.max().x
+                ));
+                assert!(touches(
+                    bbox.rect.min().y,
+                    bbox.rect.max().y,
+                    other.rect.min().y,
+                    other.rect.max().y
+                ));
+                assert!(touches(
+                    bbox.min_alt,
+                    bbox.max_alt,
+                    other.min_alt,
+                    other.max_alt
+                ));
+            }
+
+            // moving back returns to the cell
+            let east = airspace
+                .neighbor(&hash, Some(Direction::E), 0)
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                airspace.neighbor(&east, Some(Direction::W), 0).unwrap(),
+                Some(hash.clone())
+            );
+        }
+    }
+
+    // longitudes wrap around the antimeridian
+    let west = airspace
+        .encode(Coord { x: -179.9, y: 10.0 }, 100.0, 4)
+        .unwrap();
+    let east = airspace
+        .encode(Coord { x: 179.9, y: 10.0 }, 100.0, 4)
+        .unwrap();
+    assert_eq!(
+        airspace.neighbor(&west, Some(Direction::W), 0).unwrap(),
+        Some(east)
+    );
+    assert_eq!(airspace.neighbor("0", None, -1).unwrap(), None);
+    assert!(airspace.neighbors("").is_err());
+}
+
+#[test]
+fn test_parent_children() {
+    let airspace = Geohash3D::default();
+    let mut seed = 43;
+    for _ in 0..200 {
+        let c = Coord {
+            x: next(&mut seed) * 359.0 - 179.5,
+            y: next(&mut seed) * 179.0 - 89.5,
+        };
+        let alt = next(&mut seed) * 20_500.0 - 500.0;
+        let hash = airspace.encode(c, alt, 7).unwrap();
+        assert_eq!(
+            airspace.parent(&hash).unwrap(),
+            airspace.encode(c, alt, 6).unwrap()
+        );
+
+        let children = airspace.children(&hash).unwrap();
+        assert_eq!(children.len(), 32);
+        assert!(children.contains(&airspace.encode(c, alt, 8).unwrap()));
+        let bbox = airspace.decode_bbox(&hash).unwrap();
+        let volume: f64 = children
+            .iter()
+            .map(|child| {
+                let b = airspace.decode_bbox(child).unwrap();
+                assert!(bbox.contains(b.rect.min(), b.min_alt));
+                assert!(bbox.contains(b.rect.max(), b.max_alt));
+                b.rect.width() * b.rect.height() * (b.max_alt - b.min_alt)
+            })
+            .sum();
+        let expected = bbox.rect.width() * bbox.rect.height() * (bbox.max_alt - bbox.min_alt);
+        assert!((volume - expected).abs() < expected * 1e-9);
+    }
+
+    assert!(airspace.parent("8").is_err());
+    assert!(airspace.parent("8a").is_err());
+    assert!(airspace.children("8dn7k8zk8dn7").is_err());
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::next;
+use geohash::{
+    decode_bbox_int, decode_hilbert_bbox, decode_hilbert_bbox_int, encode, encode_hilbert,
+    encode_hilbert_int, encode_int, hash_to_hilbert, hilbert_neighbors, hilbert_rect_to_ranges,
+    hilbert_to_hash, hilbert_to_z_order, neighbors, z_order_to_hilbert, Coord, Rect,
+};
+
+fn random_coord(seed: &mut u64) -> Coord<f64> {
+    Coord {
+        x: next(seed) * 360.0 - 180.0,
+        y: next(seed) * 180.0 - 90.0,
+    }
+}
+
+#[test]
+fn test_curve_is_continuous() {
+    for bits in 1..=14 {
+        let mut previous: Option<Rect<f64>> = None;
+        for key in 0..1u64 << bits {
+            let int_hash = hilbert_to_z_order(key, bits).unwrap();
+            assert_eq!(z_order_to_hilbert(int_hash, bits).unwrap(), key);
+
+            // every cell shares an edge with the one before it on the curve
+            let cell = decode_hilbert_bbox_int(key, bits).unwrap();
+            assert_eq!(cell, decode_bbox_int(int_hash, bits).unwrap());
+            if let Some(prev) = previous {
+                let dx = (cell.min().x - prev.min().x).abs() / cell.width();
+                let dy = (cell.min().y - prev.min().y).abs() / cell.height();
+                assert!(
+                    (dx - 1.0).abs() < 1e-9 && dy < 1e-9 || dx < 1e-9 && (dy - 1.0).abs() < 1e-9,
+                    "{} bits, key {}",
+                    bits,
+                    key
+                );
+            }
+            previous = Some(cell);
+        }
+    }
+}
+
+#[test]
+fn test_encode_decode_hilbert() {
+    let mut seed = 17;
+    for _ in 0..1000 {
+        let c = random_coord(&mut seed);
+        for bits in [1, 2, 5, 24, 25, 52, 63, 64] {
+            let key = encode_hilbert_int(c, bits).unwrap();
+            assert_eq!(
+                z_order_to_hilbert(encode_int(c, bits).unwrap(), bits).unwrap(),
+                key
+            );
+            let cell = decode_hilbert_bbox_int(key, bits).unwrap();
+            assert!(
+                cell.min().x <= c.x && c.x <= cell.max().x,
+                "{:?} {}",
+                c,
+                bits
+            );
+            assert!(
+                cell.min().y <= c.y && c.y <= cell.max().y,
+                "{:?} {}",
+                c,
+                bits
+            );
+        }
+        for len in [1, 4, 7, 12] {
+            let hash = encode(c, len).unwrap();
+            let key = encode_hilbert(c, len).unwrap();
+            assert_eq!(hash_to_hilbert(&hash).unwrap(), key);
+            assert_eq!(hilbert_to_hash(&key).unwrap(), hash);
+            assert_eq!(
+                decode_hilbert_bbox(&key).unwrap(),
+                geohash::decode_bbox(&hash).unwrap()
+            );
+        }
+    }
+
+    let hash = "9q60y60rhs";
+    let key = hash_to_hilbert(hash).unwrap();
+    let around = neighbors(hash).unwrap();
+    let hilbert_around = hilbert_neighbors(&key).unwrap();
+    assert_eq!(hilbert_around.ne, hash_to_hilbert(&around.ne).unwrap());
+    assert_eq!(hilbert_around.sw, hash_to_hilbert(&around.sw).unwrap());
+    assert_eq!(hilbert_to_hash(&hilbert_around.w).unwrap(), around.w);
+
+    assert!(encode_hilbert_int(Coord { x: 0.0, y: 0.0 }, 65).is_err());
+    assert!(encode_hilbert(Coord { x: 0.0, y: 0.0 }, 13).is_err());
+    assert!(hilbert_to_hash("abc").is_err());
+}
+
+#[test]
+fn test_hilbert_rect_to_ranges() {
+    let mut seed = 23;
+    for _ in 0..200 {
+        let (a, b) = (random_coord(&mut seed), random_coord(&mut seed));
+        let size = next(&mut seed) * 0.5;
+        let rect = Rect::new(
+            a,
+            Coord {
+                x: (a.x + (b.x - a.x) * size),
+                y: (a.y + (b.y - a.y) * size),
+            },
+        );
+        for (bits, max_ranges) in [(15, 4), (32, 16), (63, 64)] {
+            let ranges = hilbert_rect_to_ranges(rect, bits, max_ranges).unwrap();
+            assert!(!ranges.is_empty() && ranges.len() <= max_ranges);
+            assert!(ranges.windows(2).all(|w| w[0].end() + 1 < *w[1].start()));
+            for _ in 0..50 {
+                let p = Coord {
+                    x: rect.min().x + next(&mut seed) * rect.width(),
+                    y: rect.min().y + next(&mut seed) * rect.height(),
+                };
+                let key = encode_hilbert_int(p, bits).unwrap();
+                assert!(
+                    ranges.iter().any(|r| r.contains(&key)),
+                    "{:?} {}",
+                    rect,
+                    bits
+                );
+            }
+        }
+    }
+
+    // a single cell is a single range
+    let cell = decode_hilbert_bbox_int(12345, 20).unwrap();
+    let inside = Rect::new(
+        Coord {
+            x: cell.min().x + cell.width() / 4.0,
+            y: cell.min().y + cell.height() / 4.0,
+        },
+        Coord {
+            x: cell.max().x - cell.width() / 4.0,
+            y: cell.max().y - cell.height() / 4.0,
+        },
+    );
+    let ranges = hilbert_rect_to_ranges(inside, 20, 4).unwrap();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0], 12345..=12345);
+}
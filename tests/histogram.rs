@@ -0,0 +1,177 @@
+mod common;
+
+use common::next;
+use geohash::{decode_bbox, encode, neighbors, Coord, GeohashHistogram};
+use serde::Deserialize;
+
+fn points(seed: &mut u64, n: usize) -> Vec<(Coord<f64>, f64)> {
+    (0..n)
+        .map(|_| {
+            let c = Coord {
+                x: 13.0 + next(seed) * 0.5,
+                y: 52.3 + next(seed) * 0.3,
+            };
+            (c, (next(seed) * 100.0).round())
+        })
+        .collect()
+}
+
+fn histogram(precision: usize, points: &[(Coord<f64>, f64)]) -> GeohashHistogram {
+    let mut histogram = GeohashHistogram::new(precision).unwrap();
+    for &(c, weight) in points {
+        histogram.add(c, weight).unwrap();
+    }
+    histogram
+}
+
+#[test]
+fn merge_and_rebucket() {
+    let mut seed = 7;
+    let points = points(&mut seed, 2000);
+
+    // map-reduce over chunks gives the histogram of all points
+    let all = histogram(6, &points);
+    let mut merged = GeohashHistogram::new(6).unwrap();
+    for chunk in points.chunks(300) {
+        merged.merge(&histogram(6, chunk)).unwrap();
+    }
+    assert_eq!(merged, all);
+    assert_eq!(all.iter().map(|(_, s)| s.count).sum::<u64>(), 2000);
+    assert_eq!(
+        merged
+            .merge(&GeohashHistogram::new(5).unwrap())
+            .unwrap_err()
+            .to_string(),
+        "Precision mismatch: expected 6, found 5"
+    );
+
+    // aggregating the parents is the same as counting at the coarser precision
+    for precision in 1..=6 {
+        assert_eq!(
+            all.rebucket(precision).unwrap(),
+            histogram(precision, &points)
+        );
+    }
+    assert!(all.rebucket(7).is_err());
+    assert!(all.rebucket(0).is_err());
+
+    let top = all.top_k(5);
+    assert_eq!(top.len(), 5);
+    let fifth = top[4].1.sum;
+    assert!(top.windows(2).all(|w| w[0].1.sum >= w[1].1.sum));
+    assert!(all.iter().filter(|(_, s)| s.sum > fifth).count() < 5);
+    assert!(merged.add(Coord { x: 0.0, y: 0.0 }, f64::NAN).is_err());
+}
+
+#[test]
+fn smooth() {
+    let mut seed = 11;
+    let points = points(&mut seed, 500);
+    let histogram = histogram(5, &points);
+    let smoothed = histogram.smooth().unwrap();
+
+    for (hash, stats) in smoothed.iter() {
+        let around = neighbors(hash).unwrap();
+        let block = [
+            hash, &around.n, &around.ne, &around.e, &around.se, &around.s, &around.sw, &around.w,
+            &around.nw,
+        ];
+        let (mut count, mut sum) = (0, 0.0);
+        for &(c, weight) in &points {
+            if block.contains(&encode(c, 5).unwrap().as_str()) {
+                count += 1;
+                sum += weight;
+            }
+        }
+        assert_eq!(stats.count, count, "{}", hash);
+        assert!((stats.sum - sum).abs() < 1e-6, "{}", hash);
+    }
+    // every cell with points and every cell around them
+    for (hash, _) in histogram.iter() {
+        let around = neighbors(hash).unwrap();
+        assert!(smoothed.get(hash).is_some() && smoothed.get(&around.ne).is_some());
+    }
+}
+
+#[test]
+fn smooth_poles() {
+    // a cell along the north pole, whose wrapping neighbors are along the south pole
+    let mut histogram = GeohashHistogram::new(3).unwrap();
+    histogram.add(Coord { x: 10.0, y: 89.99 }, 2.0).unwrap();
+    let hash = encode(Coord { x: 10.0, y: 89.99 }, 3).unwrap();
+    let smoothed = histogram.smooth().unwrap();
+
+    assert_eq!(smoothed.len(), 6);
+    assert!(smoothed.get(&hash).is_some());
+    for (cell, stats) in smoothed.iter() {
+        // the cell and the cells south of it, and nothing along the south pole
+        assert!(decode_bbox(cell).unwrap().min().y > 85.0, "{}", cell);
+        assert_eq!((stats.count, stats.sum), (1, 2.0));
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Row {
+    geohash: String,
+    count: u64,
+    sum: f64,
+    mean: f64,
+}
+
+#[test]
+fn export() {
+    let mut seed = 3;
+    let histogram = histogram(4, &points(&mut seed, 300));
+
+    let csv = histogram.to_csv();
+    let rows: Vec<Row> = csv::Reader::from_reader(csv.as_bytes())
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(rows.len(), histogram.len());
+    for (row, (hash, stats)) in rows.iter().zip(histogram.iter()) {
+        assert_eq!(row.geohash, hash);
+        assert_eq!(
+            (row.count, row.sum, row.mean),
+            (stats.count, stats.sum, stats.mean())
+        );
+    }
+
+    let json = histogram.to_geojson();
+    assert!(json.starts_with(r#"{"type":"FeatureCollection","features":[{"type":"Feature""#));
+    assert_eq!(json.matches(r#""type":"Polygon""#).count(), histogram.len());
+    let (hash, stats) = histogram.iter().next().unwrap();
+    let rect = decode_bbox(hash).unwrap();
+    let ring = format!(
+        "[[[{0},{1}],[{2},{1}],[{2},{3}],[{0},{3}],[{0},{1}]]]",
+        rect.min().x,
+        rect.min().y,
+        rect.max().x,
+        rect.max().y
+    );
+    assert!(json.contains(&ring));
+    assert!(json.contains(&format!(
+        r#""properties":{{"geohash":"{}","count":{},"sum":{},"mean":{}}}"#,
+        hash,
+        stats.count,
+        stats.sum,
+        stats.mean()
+    )));
+    assert_eq!(
+        GeohashHistogram::new(4).unwrap().to_geojson(),
+        r#"{"type":"FeatureCollection","features":[]}"#
+    );
+
+    // sums overflowing to infinity are not valid JSON numbers
+    let mut overflow = GeohashHistogram::new(4).unwrap();
+    for _ in 0..2 {
+        overflow.add(Coord { x: 2.35, y: 48.85 }, f64::MAX).unwrap();
+    }
+    let json = overflow.to_geojson();
+    assert!(
+        json.contains(r#""count":2,"sum":null,"mean":null"#),
+        "{}",
+        json
+    );
+    assert!(!json.contains("inf"));
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::{haversine, next};
+use geohash::{Coord, GeohashIndex};
+
+fn points(n: usize, mut seed: u64) -> Vec<Coord<f64>> {
+    (0..n)
+        .map(|_| Coord {
+            x: next(&mut seed) * 360.0 - 180.0,
+            y: next(&mut seed) * 180.0 - 90.0,
+        })
+        .collect()
+}
+
+fn brute_force(pts: &[Coord<f64>], center: Coord<f64>) -> Vec<(usize, f64)> {
+    let mut all: Vec<_> = pts
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i, haversine(center, *p)))
+        .collect();
+    all.sort_by(|a, b| a.1.total_cmp(&b.1));
+    all
+}
+
+#[test]
+fn test_knn() {
+    let pts = points(5000, 7);
+    let mut index = GeohashIndex::new();
+    for (i, p) in pts.iter().enumerate() {
+        index.insert(*p, i).unwrap();
+    }
+    assert_eq!(index.len(), 5000);
+
+    let queries = [
+        Coord { x: 0.0, y: 0.0 },
+        Coord { x: 179.99, y: 10.0 },
+        Coord {
+            x: -179.99,
+            y: -45.0,
+        },
+        Coord { x: 30.0, y: 89.9 },
+        Coord { x: -60.0, y: -90.0 },
+    ];
+    for center in queries {
+        for k in [1, 5, 50] {
+            let expected = brute_force(&pts, center);
+            let found = index.knn(center, k).unwrap();
+            assert_eq!(found.len(), k);
+            for (hit, exp) in found.iter().zip(&expected) {
+                assert!((hit.2 - exp.1).abs() < 1e-6, "{:?} k={}", center, k);
+            }
+        }
+    }
+
+    // asking for more values than stored returns everything
+    assert_eq!(
+        index.knn(Coord { x: 0.0, y: 0.0 }, 6000).unwrap().len(),
+        5000
+    );
+}
+
+#[test]
+fn test_within_radius() {
+    let pts = points(5000, 11);
+    let mut index = GeohashIndex::new();
+    for (i, p) in pts.iter().enumerate() {
+        index.insert(*p, i).unwrap();
+    }
+
+    let queries = [
+        Coord { x: 12.0, y: 40.0 },
+        Coord { x: 180.0, y: 0.0 },
+        Coord { x: 0.0, y: 90.0 },
+    ];
+    for center in queries {
+        for meters in [10_000.0, 300_000.0, 2_000_000.0] {
+            let mut expected: Vec<_> = brute_force(&pts, center)
+                .into_iter()
+                .filter(|&(_, d)| d <= meters)
+                .map(|(i, _)| i)
+                .collect();
+            let mut found: Vec<_> = index
+                .within_radius(center, meters)
+                .unwrap()
+                .into_iter()
+                .map(|(_, &i, _)| i)
+                .collect();
+            expected.sort();
+            found.sort();
+            assert_eq!(found, expected, "{:?} {}", center, meters);
+        }
+    }
+
+    assert!(index
+        .within_radius(Coord { x: 200.0, y: 0.0 }, 1.0)
+        .is_err());
+}
+
+#[test]
+fn test_remove() {
+    let mut index = GeohashIndex::new();
+    let c = Coord { x: 10.0, y: 10.0 };
+    index.insert(c, "a").unwrap();
+    index.insert(c, "b").unwrap();
+
+    assert_eq!(index.remove(c, &"c"), None);
+    assert_eq!(index.remove(c, &"a"), Some("a"));
+    assert_eq!(index.len(), 1);
+    assert_eq!(index.knn(c, 1).unwrap()[0].1, &"b");
+    assert_eq!(index.remove(c, &"b"), Some("b"));
+    assert!(index.is_empty());
+    assert!(index.knn(c, 3).unwrap().is_empty());
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::{haversine, next};
+use geohash::{join_within, Coord};
+
+// points clustered around a few centers, so that there are pairs at every scale
+fn points(seed: &mut u64, n: usize, centers: &[Coord<f64>], spread: f64) -> Vec<Coord<f64>> {
+    (0..n)
+        .map(|_| {
+            let center = centers[(next(seed) * centers.len() as f64) as usize];
+            let mut x = center.x + (next(seed) * 2.0 - 1.0) * spread;
+            if x >= 180.0 {
+                x -= 360.0;
+            } else if x < -180.0 {
+                x += 360.0;
+            }
+            let y = (center.y + (next(seed) * 2.0 - 1.0) * spread).clamp(-89.999, 89.999);
+            Coord { x, y }
+        })
+        .collect()
+}
+
+fn brute_force(left: &[Coord<f64>], right: &[Coord<f64>], meters: f64) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, a) in left.iter().enumerate() {
+        for (j, b) in right.iter().enumerate() {
+            if haversine(*a, *b) <= meters {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+#[test]
+fn test_join_within() {
+    let mut seed = 89;
+    let centers = [
+        Coord { x: 2.35, y: 48.85 },
+        Coord {
+            x: 179.99,
+            y: -16.5,
+        },
+        Coord { x: -70.0, y: -85.0 },
+        Coord { x: 10.0, y: 89.9 },
+        Coord { x: 0.0, y: 0.0 },
+    ];
+    for &(meters, spread) in [
+        (10.0, 0.001),
+        (1000.0, 0.05),
+        (50_000.0, 2.0),
+        (2_000_000.0, 40.0),
+        (30_000_000.0, 180.0),
+    ]
+    .iter()
+    {
+        let left = points(&mut seed, 300, &centers, spread);
+        let right = points(&mut seed, 300, &centers, spread);
+        let pairs = join_within(
+            left.iter().copied().zip(0..),
+            right.iter().copied().zip(0..),
+            meters,
+        )
+        .unwrap();
+
+        let found: Vec<(usize, usize)> = pairs.iter().map(|&(i, j, _)| (i, j)).collect();
+        let expected = brute_force(&left, &right, meters);
+        // allow for rounding between the two implementations right at the threshold
+        let far = |&(i, j): &(usize, usize)| {
+            (haversine(left[i], right[j]) - meters).abs() > 1e-6 * meters.max(1.0)
+        };
+        assert_eq!(
+            found.iter().filter(|p| far(p)).collect::<Vec<_>>(),
+            expected.iter().filter(|p| far(p)).collect::<Vec<_>>(),
+            "{}",
+            meters
+        );
+        assert!(!expected.is_empty());
+        for &(i, j, d) in pairs.iter() {
+            assert!((d - haversine(left[i], right[j])).abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn test_empty_and_invalid() {
+    let paris = (Coord { x: 2.35, y: 48.85 }, 1);
+    assert!(
+        join_within(Vec::<(Coord<f64>, u8)>::new(), vec![paris], 100.0)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        join_within(vec![paris], Vec::<(Coord<f64>, u8)>::new(), 100.0)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        join_within(vec![paris], vec![paris], 0.0).unwrap(),
+        [(1, 1, 0.0)]
+    );
+    assert!(join_within(vec![paris], vec![paris], -1.0)
+        .unwrap()
+        .is_empty());
+    assert!(join_within(vec![paris], vec![paris], f64::NAN)
+        .unwrap()
+        .is_empty());
+    assert!(join_within(vec![paris], vec![(Coord { x: 0.0, y: 91.0 }, 2)], 1.0).is_err());
+    assert!(join_within(vec![(Coord { x: 181.0, y: 0.0 }, 2)], vec![paris], 1.0).is_err());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_join_within() {
+    use geohash::par_join_within;
+
+    let mut seed = 97;
+    let centers = [Coord { x: 2.35, y: 48.85 }, Coord { x: -122.4, y: 37.8 }];
+    let left = points(&mut seed, 2000, &centers, 0.05);
+    let right = points(&mut seed, 2000, &centers, 0.05);
+    let sequential = join_within(
+        left.iter().copied().zip(0..),
+        right.iter().copied().zip(0..),
+        500.0,
+    )
+    .unwrap();
+    let parallel = par_join_within(
+        left.iter().copied().zip(0..),
+        right.iter().copied().zip(0..),
+        500.0,
+    )
+    .unwrap();
+    assert!(!sequential.is_empty());
+    assert_eq!(sequential, parallel);
+}
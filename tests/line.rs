@@ -0,0 +1,192 @@
+mod common;
+
+use common::next;
+use geo_types::{line_string, LineString};
+use geohash::{cover_line, decode_bbox, encode, neighbors, Coord};
+
+// the shortest way from `a` to `b`, with longitudes wrapped back into range
+fn point_along(a: Coord<f64>, b: Coord<f64>, t: f64) -> Coord<f64> {
+    let mut dx = b.x - a.x;
+    if dx > 180.0 {
+        dx -= 360.0;
+    } else if dx < -180.0 {
+        dx += 360.0;
+    }
+    let mut x = a.x + t * dx;
+    if x >= 180.0 {
+        x -= 360.0;
+    } else if x < -180.0 {
+        x += 360.0;
+    }
+    Coord {
+        x,
+        y: a.y + t * (b.y - a.y),
+    }
+}
+
+fn check_cover(line: &LineString<f64>, len: usize) {
+    let cells = cover_line(line, len).unwrap();
+    let hashes: Vec<&str> = cells.iter().map(|cell| cell.hash.as_str()).collect();
+
+    // consecutive cells are different, and share an edge
+    for w in cells.windows(2) {
+        let around = neighbors(&w[0].hash).unwrap();
+        let adjacent = [&around.n, &around.e, &around.s, &around.w];
+        assert!(adjacent.contains(&&w[1].hash), "{:?} {:?}", line, hashes);
+        let (exit, entry) = (w[0].exit, w[1].entry);
+        assert!(exit == entry || (exit.x.abs() == 180.0 && entry.x == -exit.x));
+    }
+
+    // the positions are on the cells
+    let eps = 1e-9;
+    for cell in cells.iter() {
+        let bbox = decode_bbox(&cell.hash).unwrap();
+        for c in [cell.entry, cell.exit] {
+            assert!(bbox.min().x - eps <= c.x && c.x <= bbox.max().x + eps);
+            assert!(bbox.min().y - eps <= c.y && c.y <= bbox.max().y + eps);
+        }
+    }
+    assert_eq!(cells[0].entry, line.0[0]);
+    assert_eq!(cells.last().unwrap().exit, *line.0.last().unwrap());
+
+    // points along the line are in the cells, in order
+    let mut position = 0;
+    for segment in line.lines() {
+        for i in 0..=200 {
+            let c = point_along(segment.start, segment.end, i as f64 / 200.0);
+            // skip points too close to a boundary to tell on which side they are
+            let bbox = decode_bbox(&encode(c, len).unwrap()).unwrap();
+            let margin = 1e-9;
+            if (c.x - bbox.min().x).abs() < margin
+                || (c.x - bbox.max().x).abs() < margin
+                || (c.y - bbox.min().y).abs() < margin
+                || (c.y - bbox.max().y).abs() < margin
+            {
+                continue;
+            }
+            let hash = encode(c, len).unwrap();
+            let found = hashes[position..].iter().position(|h| *h == hash);
+            assert!(found.is_some(), "{:?} {} {:?}", line, hash, hashes);
+            position += found.unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_cover_line() {
+    let mut seed = 59;
+    for len in [2, 3, 5] {
+        for _ in 0..200 {
+            let points: Vec<Coord<f64>> = (0..4)
+                .map(|_| Coord {
+                    x: next(&mut seed) * 359.0 - 179.5,
+                    y: next(&mut seed) * 170.0 - 85.0,
+                })
+                .collect();
+            // short tracks, and far apart points
+            let scale = if len == 5 { 0.2 } else { 10.0 };
+            let track: Vec<Coord<f64>> = points
+                .iter()
+                .map(|p| Coord {
+                    x: points[0].x + (p.x - points[0].x) * scale / 360.0,
+                    y: points[0].y + (p.y - points[0].y) * scale / 180.0,
+                })
+                .collect();
+            check_cover(&LineString::from(track), len);
+            if len == 2 {
+                check_cover(&LineString::from(points), len);
+            }
+        }
+    }
+
+    // a single point, and an empty line
+    let point = line_string![(x: 2.35, y: 48.85)];
+    let cells = cover_line(&point, 6).unwrap();
+    assert_eq!(cells.len(), 1);
+    assert_eq!(
+        cells[0].hash,
+        encode(Coord { x: 2.35, y: 48.85 }, 6).unwrap()
+    );
+    assert!(cover_line(&LineString::new(vec![]), 6).unwrap().is_empty());
+
+    // exactly along a cell edge, and through corners
+    let diagonal = line_string![(x: -22.5, y: -11.25), (x: 22.5, y: 11.25)];
+    let cells = cover_line(&diagonal, 2).unwrap();
+    assert_eq!(
+        cells.first().unwrap().hash,
+        encode(
+            Coord {
+                x: -22.5,
+                y: -11.25
+            },
+            2
+        )
+        .unwrap()
+    );
+    assert_eq!(
+        cells.last().unwrap().hash,
+        encode(Coord { x: 22.5, y: 11.25 }, 2).unwrap()
+    );
+    // four columns and four rows, one at a time
+    assert_eq!(cells.len(), 9);
+
+    // through a single corner, the cell east of it is only touched at the corner
+    let corner = Coord { x: 0.0, y: 0.0 };
+    let cells = cover_line(&line_string![(x: -22.5, y: -22.5), (x: 22.5, y: 22.5)], 1).unwrap();
+    let hashes: Vec<&str> = cells.iter().map(|cell| cell.hash.as_str()).collect();
+    assert_eq!(hashes, ["7", "k", "s"]);
+    assert_eq!((cells[1].entry, cells[1].exit), (corner, corner));
+    assert_eq!((cells[0].exit, cells[2].entry), (corner, corner));
+}
+
+#[test]
+fn test_antimeridian() {
+    let line = line_string![(x: 179.5, y: 10.0), (x: -179.5, y: 10.5)];
+    let cells = cover_line(&line, 2).unwrap();
+    let hashes: Vec<&str> = cells.iter().map(|cell| cell.hash.as_str()).collect();
+    assert_eq!(
+        hashes,
+        [
+            encode(Coord { x: 179.5, y: 10.0 }, 2).unwrap(),
+            encode(Coord { x: -179.5, y: 10.5 }, 2).unwrap()
+        ]
+    );
+    assert_eq!(cells[0].exit.x, 180.0);
+    assert_eq!(cells[1].entry.x, -180.0);
+    assert_eq!(cells[0].exit.y, cells[1].entry.y);
+    assert!((cells[0].exit.y - 10.25).abs() < 1e-12);
+
+    // and back again, westwards
+    let line = line_string![(x: -170.0, y: -40.0), (x: 170.0, y: -40.0), (x: 175.0, y: -40.0)];
+    check_cover(&line, 3);
+    let cells = cover_line(&line, 1).unwrap();
+    let hashes: Vec<&str> = cells.iter().map(|cell| cell.hash.as_str()).collect();
+    assert_eq!(hashes, ["2", "r"]);
+
+    let mut seed = 67;
+    for _ in 0..200 {
+        let a = Coord {
+            x: 180.0 - next(&mut seed) * 5.0,
+            y: next(&mut seed) * 160.0 - 80.0,
+        };
+        let b = Coord {
+            x: -180.0 + next(&mut seed) * 5.0,
+            y: a.y + next(&mut seed) * 2.0 - 1.0,
+        };
+        check_cover(&LineString::from(vec![a, b]), 3);
+        check_cover(&LineString::from(vec![b, a]), 4);
+    }
+
+    // a segment across the antimeridian that doesn't move in longitude
+    for line in [
+        line_string![(x: 180.0, y: 10.0), (x: -180.0, y: 10.0)],
+        line_string![(x: 180.0, y: 10.0), (x: -180.0, y: 20.0)],
+    ] {
+        // the positions are checked to be on the cells, so none of them can be NaN
+        check_cover(&line, 2);
+        check_cover(&LineString::from(vec![line.0[1], line.0[0]]), 3);
+    }
+
+    assert!(cover_line(&line, 13).is_err());
+    assert!(cover_line(&line_string![(x: 0.0, y: 0.0), (x: 190.0, y: 0.0)], 3).is_err());
+}
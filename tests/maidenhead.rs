@@ -0,0 +1,88 @@
+use geohash::{
+    decode_bbox, decode_maidenhead, encode_maidenhead, maidenhead_to_hashes, Coord, Rect,
+};
+
+#[test]
+fn test_encode_decode_maidenhead() {
+    let cases = [
+        // Newington, Connecticut
+        (-72.727, 41.714, "FN31pr21"),
+        // Munich
+        (11.576, 48.137, "JN58sd92"),
+        // Montevideo
+        (-56.164, -34.905, "GF15wc02"),
+        // Washington, DC
+        (-77.035, 38.899, "FM18lv55"),
+        // Wellington
+        (174.776, -41.286, "RE78jr31"),
+        (-180.0, -90.0, "AA00aa00"),
+        (180.0, 90.0, "RR99xx99"),
+    ];
+    for (x, y, locator) in cases {
+        let c = Coord { x, y };
+        for len in [2, 4, 6, 8] {
+            assert_eq!(encode_maidenhead(c, len).unwrap(), &locator[..len]);
+            let square = decode_maidenhead(&locator[..len]).unwrap();
+            assert!(square.min().x <= x && x <= square.max().x);
+            assert!(square.min().y <= y && y <= square.max().y);
+            assert_eq!(
+                encode_maidenhead(square.center(), len).unwrap(),
+                &locator[..len]
+            );
+        }
+    }
+
+    let square = decode_maidenhead("fn31PR").unwrap();
+    let expected = Rect::new(
+        Coord {
+            x: -72.75,
+            y: 41.708333333333336,
+        },
+        Coord {
+            x: -72.66666666666667,
+            y: 41.75,
+        },
+    );
+    assert!((square.min().x - expected.min().x).abs() < 1e-12);
+    assert!((square.min().y - expected.min().y).abs() < 1e-12);
+    assert!((square.max().x - expected.max().x).abs() < 1e-12);
+    assert!((square.max().y - expected.max().y).abs() < 1e-12);
+
+    for locator in [
+        "",
+        "F",
+        "FN3",
+        "SN",
+        "FNA1",
+        "FN31py",
+        "FN31pr2x",
+        "FN31pr2100",
+    ] {
+        assert!(decode_maidenhead(locator).is_err(), "{}", locator);
+    }
+    assert!(encode_maidenhead(Coord { x: 0.0, y: 0.0 }, 5).is_err());
+    assert!(encode_maidenhead(Coord { x: 0.0, y: 0.0 }, 10).is_err());
+    assert!(encode_maidenhead(Coord { x: 181.0, y: 0.0 }, 4).is_err());
+}
+
+#[test]
+fn test_maidenhead_to_hashes() {
+    for locator in ["FN31", "FN31pr", "JN58sd92", "RE78jr"] {
+        let square = decode_maidenhead(locator).unwrap();
+        for len in [3, 5, 6] {
+            let hashes = maidenhead_to_hashes(locator, len).unwrap();
+            assert!(!hashes.is_empty());
+            let mut area = 0.0;
+            for hash in &hashes {
+                let cell = decode_bbox(hash).unwrap();
+                let width = cell.max().x.min(square.max().x) - cell.min().x.max(square.min().x);
+                let height = cell.max().y.min(square.max().y) - cell.min().y.max(square.min().y);
+                assert!(width > 0.0 && height > 0.0, "{} {}", locator, hash);
+                area += width * height;
+            }
+            // the cells don't overlap and together cover the whole square
+            let expected = square.width() * square.height();
+            assert!((area - expected).abs() < expected * 1e-9, "{}", locator);
+        }
+    }
+}
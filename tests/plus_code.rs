@@ -0,0 +1,161 @@
+use geohash::{
+    decode_bbox, decode_plus_code, encode_plus_code, hash_to_plus_code, is_full_plus_code,
+    is_short_plus_code, is_valid_plus_code, plus_code_to_hashes, recover_plus_code,
+    shorten_plus_code, Coord,
+};
+
+// code, latitude, longitude and length, from the Open Location Code reference test data
+const ENCODING: &[(&str, f64, f64, usize)] = &[
+    ("7FG49Q00+", 20.375, 2.775, 6),
+    ("7FG49QCJ+2V", 20.3700625, 2.7821875, 10),
+    ("7FG49QCJ+2VX", 20.3701125, 2.782234375, 11),
+    ("7FG49QCJ+2VXGJ", 20.3701135, 2.78223535156, 13),
+    ("8FVC2222+22", 47.0000625, 8.0000625, 10),
+    ("62G20000+", 0.5, -179.5, 4),
+    ("22220000+", -89.5, -179.5, 4),
+    ("7FG40000+", 20.5, 2.5, 4),
+    ("22222222+22", -89.9999375, -179.9999375, 10),
+    ("6VGX0000+", 0.5, 179.5, 4),
+    ("6FH32222+222", 1.0, 1.0, 11),
+    ("CFX30000+", 90.0, 1.0, 4),
+    ("62H20000+", 1.0, 180.0, 4),
+];
+
+#[test]
+fn test_encode_decode_plus_code() {
+    for &(code, lat, lng, len) in ENCODING {
+        let c = Coord { x: lng, y: lat };
+        assert_eq!(encode_plus_code(c, len).unwrap(), code, "{:?}", c);
+        assert!(is_full_plus_code(code));
+
+        let area = decode_plus_code(code).unwrap();
+        let encoded = encode_plus_code(area.center(), len).unwrap();
+        assert_eq!(encoded, code);
+    }
+
+    let area = decode_plus_code("7FG49QCJ+2V").unwrap();
+    assert!((area.min().y - 20.37).abs() < 1e-10);
+    assert!((area.min().x - 2.782125).abs() < 1e-10);
+    assert!((area.max().y - 20.370125).abs() < 1e-10);
+    assert!((area.max().x - 2.78225).abs() < 1e-10);
+
+    // lower case codes are accepted
+    assert_eq!(decode_plus_code("7fg49qcj+2v").unwrap(), area);
+
+    assert!(encode_plus_code(Coord { x: 0.0, y: 0.0 }, 1).is_err());
+    assert!(encode_plus_code(Coord { x: 0.0, y: 0.0 }, 7).is_err());
+    assert!(encode_plus_code(Coord { x: 0.0, y: 91.0 }, 10).is_err());
+    assert!(decode_plus_code("9QCJ+2VX").is_err());
+}
+
+#[test]
+fn test_validate_plus_code() {
+    for code in ["8FWC2345+G6", "8FWC2345+G6G", "8fwc2345+", "8FWCX400+"] {
+        assert!(
+            is_valid_plus_code(code) && is_full_plus_code(code),
+            "{}",
+            code
+        );
+    }
+    for code in ["WC2345+G6g", "2345+G6", "45+G6", "+G6"] {
+        assert!(
+            is_valid_plus_code(code) && is_short_plus_code(code),
+            "{}",
+            code
+        );
+    }
+    for code in [
+        "G+",
+        "+",
+        "8FWC2345+G",
+        "8FWC2_45+G6",
+        "8FWC2η45+G6",
+        "8FWC2345+G6+",
+        "8FWC2345G6+",
+        "8FWC2300+G6",
+        "WC2300+G6g",
+        "WC2345+G",
+        "WC2300+",
+        "9mQp0Hv2+",
+        "2222000V+",
+    ] {
+        assert!(!is_valid_plus_code(code), "{}", code);
+    }
+    // padding that doesn't run up to the separator
+    for code in ["9mQp0Hv2+", "2222000V+"] {
+        assert!(decode_plus_code(code).is_err(), "{}", code);
+        assert!(plus_code_to_hashes(code, 5).is_err(), "{}", code);
+    }
+    // valid, but out of range
+    assert!(is_valid_plus_code("XX000000+") && !is_full_plus_code("XX000000+"));
+}
+
+#[test]
+fn test_shorten_recover_plus_code() {
+    let code = "9C3W9QCJ+2VX";
+    let cases = [
+        (51.3701125, -1.217765625, "+2VX"),
+        (51.3708675, -1.217765625, "CJ+2VX"),
+        (51.3693575, -1.217765625, "CJ+2VX"),
+        (51.3701125, -1.217010625, "CJ+2VX"),
+        (51.3701125, -1.218520625, "CJ+2VX"),
+        (51.3852125, -1.217765625, "9QCJ+2VX"),
+        (51.3550125, -1.217765625, "9QCJ+2VX"),
+        (51.3701125, -1.1, "9QCJ+2VX"),
+        (51.3701125, -1.3, "9QCJ+2VX"),
+    ];
+    for (lat, lng, short) in cases {
+        let reference = Coord { x: lng, y: lat };
+        assert_eq!(
+            shorten_plus_code(code, reference).unwrap(),
+            short,
+            "{:?}",
+            reference
+        );
+        assert_eq!(recover_plus_code(short, reference).unwrap(), code);
+    }
+
+    // recovery picks the nearest match, even across the equator or the antimeridian
+    let reference = Coord { x: 179.9, y: 0.0 };
+    let full = encode_plus_code(
+        Coord {
+            x: -179.95,
+            y: -0.01,
+        },
+        10,
+    )
+    .unwrap();
+    let short = &full[4..];
+    assert_eq!(recover_plus_code(short, reference).unwrap(), full);
+
+    assert!(shorten_plus_code("9C3W0000+", Coord { x: 0.0, y: 0.0 }).is_err());
+    assert!(recover_plus_code("9QCJ+2VX", Coord { x: 0.0, y: 100.0 }).is_err());
+    assert_eq!(
+        recover_plus_code(code, Coord { x: 0.0, y: 0.0 }).unwrap(),
+        code
+    );
+}
+
+#[test]
+fn test_plus_code_geohashes() {
+    let code = "8FVC9G8F+6W";
+    let area = decode_plus_code(code).unwrap();
+    for len in [5, 7, 9] {
+        let hashes = plus_code_to_hashes(code, len).unwrap();
+        assert!(!hashes.is_empty());
+        for hash in &hashes {
+            let cell = decode_bbox(hash).unwrap();
+            assert!(cell.min().x < area.max().x && area.min().x < cell.max().x);
+            assert!(cell.min().y < area.max().y && area.min().y < cell.max().y);
+        }
+    }
+
+    for hash in ["u0qj", "u0qj8d", "u0qj8dqu", "u0qj8dqu2sb"] {
+        let cell = decode_bbox(hash).unwrap();
+        let code = hash_to_plus_code(hash).unwrap().unwrap();
+        let area = decode_plus_code(&code).unwrap();
+        assert!(area.min().x <= cell.min().x && cell.max().x <= area.max().x);
+        assert!(area.min().y <= cell.min().y && cell.max().y <= area.max().y);
+    }
+    assert_eq!(hash_to_plus_code("u").unwrap(), None);
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use common::next;
+use geohash::{
+    cover_rect, encode_int, rect_to_ranges, tile_to_hashes, Coord, Rect, Tile, MAX_COVER_CELLS,
+};
+
+fn check_covered(rect: Rect<f64>, bits: u32, max_ranges: usize) {
+    let ranges = rect_to_ranges(rect, bits, max_ranges).unwrap();
+    assert!(!ranges.is_empty() && ranges.len() <= max_ranges);
+    for w in ranges.windows(2) {
+        assert!(
+            w[0].end() + 1 < *w[1].start(),
+            "ranges must be sorted and disjoint"
+        );
+    }
+
+    let mut seed = bits as u64;
+    let (min, max) = (rect.min(), rect.max());
+    let corners = [
+        min,
+        max,
+        Coord { x: min.x, y: max.y },
+        Coord { x: max.x, y: min.y },
+    ];
+    let random = (0..2000).map(|_| Coord {
+        x: min.x + next(&mut seed) * (max.x - min.x),
+        y: min.y + next(&mut seed) * (max.y - min.y),
+    });
+    for c in corners.iter().copied().chain(random) {
+        let key = encode_int(c, bits).unwrap();
+        assert!(
+            ranges.iter().any(|r| r.contains(&key)),
+            "{:?} not covered for {:?} at {} bits",
+            c,
+            rect,
+            bits
+        );
+    }
+}
+
+#[test]
+fn test_rect_to_ranges_covers_points() {
+    let rects = [
+        Rect::new(Coord { x: -122.5, y: 37.7 }, Coord { x: -122.3, y: 37.8 }),
+        Rect::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 45.0, y: 45.0 }),
+        Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 }),
+        Rect::new(Coord { x: 170.0, y: 80.0 }, Coord { x: 180.0, y: 90.0 }),
+        Rect::new(Coord { x: 12.5, y: 41.9 }, Coord { x: 12.5, y: 41.9 }),
+    ];
+    for rect in rects {
+        for bits in [5, 25, 32, 52, 60] {
+            for max_ranges in [1, 4, 16, 64] {
+                check_covered(rect, bits, max_ranges);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rect_to_ranges_exact() {
+    // a rectangle inside a single geohash cell is a single range, the north and east edges of
+    // the cell itself belong to its neighbors
+    let cell = geohash::decode_bbox("9q8y").unwrap();
+    let rect = Rect::new(
+        cell.min(),
+        Coord {
+            x: cell.max().x - 1e-9,
+            y: cell.max().y - 1e-9,
+        },
+    );
+    let key = encode_int(rect.center(), 20).unwrap();
+    let single = |r: std::ops::RangeInclusive<u64>| vec![r];
+    assert_eq!(rect_to_ranges(rect, 20, 10).unwrap(), single(key..=key));
+    assert_eq!(
+        rect_to_ranges(rect, 30, 10).unwrap(),
+        single(key << 10..=((key + 1) << 10) - 1)
+    );
+    let with_edges = rect_to_ranges(cell, 20, 10).unwrap();
+    assert_eq!(
+        with_edges
+            .iter()
+            .map(|r| r.end() - r.start() + 1)
+            .sum::<u64>(),
+        4
+    );
+
+    // the whole world
+    let world = Rect::new(
+        Coord {
+            x: -180.0,
+            y: -90.0,
+        },
+        Coord { x: 180.0, y: 90.0 },
+    );
+    assert_eq!(rect_to_ranges(world, 10, 1).unwrap(), single(0..=1023));
+
+    // more ranges can only make the cover tighter
+    let rect = Rect::new(Coord { x: 2.2, y: 48.8 }, Coord { x: 2.5, y: 48.9 });
+    let size = |max| {
+        rect_to_ranges(rect, 40, max)
+            .unwrap()
+            .iter()
+            .map(|r| r.end() - r.start() + 1)
+            .sum::<u64>()
+    };
+    assert!(size(2) >= size(8));
+    assert!(size(8) >= size(32));
+
+    // with all 64 bits, the ranges reach the hash of the north east corner of the world
+    let corner = encode_int(Coord { x: 180.0, y: 90.0 }, 64).unwrap();
+    assert_eq!(corner, u64::MAX);
+    let east = Rect::new(Coord { x: 179.9, y: 89.9 }, Coord { x: 180.0, y: 90.0 });
+    let ranges = rect_to_ranges(east, 64, 4).unwrap();
+    assert_eq!(ranges.last().map(|r| *r.end()), Some(u64::MAX));
+    assert_eq!(rect_to_ranges(world, 64, 1).unwrap(), single(0..=u64::MAX));
+
+    assert!(rect_to_ranges(rect, 0, 4).is_err());
+    assert!(rect_to_ranges(rect, 65, 4).is_err());
+}
+
+#[test]
+fn test_cover_rect_limit() {
+    let world = Rect::new(
+        Coord {
+            x: -180.0,
+            y: -90.0,
+        },
+        Coord { x: 180.0, y: 90.0 },
+    );
+    // 32^4 cells fit, 32^5 don't, and much longer geohashes fail before allocating
+    assert_eq!(cover_rect(world, 4).unwrap().len(), 1 << 20);
+    assert_eq!(
+        cover_rect(world, 5).unwrap_err().to_string(),
+        format!(
+            "Too many cells: {} are needed, at most {} are allowed",
+            1u64 << 25,
+            MAX_COVER_CELLS
+        )
+    );
+    assert!(cover_rect(world, 12).is_err());
+    assert!(tile_to_hashes(Tile::new(0, 0, 0).unwrap(), 12).is_err());
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::next;
+use geohash::{decode_bbox, encode, neighbors, relation, CellRelation, Coord, Direction};
+
+// the relation from the bounding boxes, whose edges are exact in floating point
+fn expected(a: &str, b: &str) -> CellRelation {
+    let (a, b) = (decode_bbox(a).unwrap(), decode_bbox(b).unwrap());
+    if a == b {
+        return CellRelation::Equal;
+    }
+    let inside = |outer: geo_types::Rect<f64>, inner: geo_types::Rect<f64>| {
+        outer.min().x <= inner.min().x
+            && outer.min().y <= inner.min().y
+            && inner.max().x <= outer.max().x
+            && inner.max().y <= outer.max().y
+    };
+    if inside(a, b) {
+        return CellRelation::Contains;
+    }
+    if inside(b, a) {
+        return CellRelation::Within;
+    }
+    let dlat = if a.min().y < b.max().y && b.min().y < a.max().y {
+        0
+    } else if b.min().y == a.max().y {
+        1
+    } else if b.max().y == a.min().y {
+        -1
+    } else {
+        return CellRelation::Disjoint;
+    };
+    let dlon = if a.min().x < b.max().x && b.min().x < a.max().x {
+        0
+    } else if b.min().x == a.max().x || (a.max().x == 180.0 && b.min().x == -180.0) {
+        1
+    } else if b.max().x == a.min().x || (a.min().x == -180.0 && b.max().x == 180.0) {
+        -1
+    } else {
+        return CellRelation::Disjoint;
+    };
+    let direction = [
+        [Direction::SW, Direction::S, Direction::SE],
+        [Direction::W, Direction::E, Direction::E],
+        [Direction::NW, Direction::N, Direction::NE],
+    ][(dlat + 1) as usize][(dlon + 1) as usize];
+    CellRelation::Touching(direction)
+}
+
+#[test]
+fn test_relation() {
+    let mut seed = 29;
+    for _ in 0..20_000 {
+        let c = Coord {
+            x: next(&mut seed) * 360.0 - 180.0,
+            y: next(&mut seed) * 180.0 - 90.0,
+        };
+        // a nearby point, often across the antimeridian
+        let mut x = c.x + (next(&mut seed) * 2.0 - 1.0) * 2.0;
+        if x >= 180.0 {
+            x -= 360.0;
+        } else if x < -180.0 {
+            x += 360.0;
+        }
+        let d = Coord {
+            x,
+            y: (c.y + (next(&mut seed) * 2.0 - 1.0) * 2.0).clamp(-90.0, 90.0),
+        };
+        let a = encode(c, 1 + (next(&mut seed) * 6.0) as usize).unwrap();
+        let b = encode(d, 1 + (next(&mut seed) * 6.0) as usize).unwrap();
+        assert_eq!(relation(&a, &b).unwrap(), expected(&a, &b), "{} {}", a, b);
+    }
+}
+
+#[test]
+fn test_relation_neighbors() {
+    let mut seed = 31;
+    for _ in 0..2000 {
+        let c = Coord {
+            x: next(&mut seed) * 360.0 - 180.0,
+            y: next(&mut seed) * 170.0 - 85.0,
+        };
+        let hash = encode(c, 1 + (next(&mut seed) * 8.0) as usize).unwrap();
+        let rect = decode_bbox(&hash).unwrap();
+        // the neighbors of the cells along the poles wrap around them
+        if rect.min().y == -90.0 || rect.max().y == 90.0 {
+            continue;
+        }
+        let around = neighbors(&hash).unwrap();
+        for (cell, direction) in [
+            (&around.n, Direction::N),
+            (&around.ne, Direction::NE),
+            (&around.e, Direction::E),
+            (&around.se, Direction::SE),
+            (&around.s, Direction::S),
+            (&around.sw, Direction::SW),
+            (&around.w, Direction::W),
+            (&around.nw, Direction::NW),
+        ] {
+            assert_eq!(
+                relation(&hash, cell).unwrap(),
+                CellRelation::Touching(direction)
+            );
+        }
+        assert_eq!(relation(&hash, &hash).unwrap(), CellRelation::Equal);
+        if hash.len() > 1 {
+            assert_eq!(relation(&hash[..1], &hash).unwrap(), CellRelation::Contains);
+            assert_eq!(relation(&hash, &hash[..1]).unwrap(), CellRelation::Within);
+        }
+    }
+    assert_eq!(
+        relation("b", "0").unwrap(),
+        CellRelation::Disjoint,
+        "cells don't wrap around the poles"
+    );
+    assert!(relation("u09", "a").is_err());
+    assert!(relation("", "u09").is_err());
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::next;
+use geohash::{
+    decode_bbox, encode, hash_to_s2_cells, rect_to_s2_cells, s2_cell_id, s2_cell_to_hashes, Coord,
+    Rect,
+};
+
+// the parent of a cell id, one level up
+fn parent(id: u64) -> u64 {
+    let lsb = id & id.wrapping_neg();
+    (id & !(lsb * 4 - 1)) | (lsb * 4)
+}
+
+#[test]
+fn test_s2_cell_id() {
+    // the six faces of the cube
+    for (x, y, face) in [
+        (0.0, 0.0, 0),
+        (90.0, 0.0, 1),
+        (0.0, 90.0, 2),
+        (180.0, 0.0, 3),
+        (-90.0, 0.0, 4),
+        (0.0, -90.0, 5),
+    ] {
+        let id = s2_cell_id(Coord { x, y }, 0).unwrap();
+        assert_eq!(id, (face << 61) | 1 << 60, "{} {}", x, y);
+    }
+
+    // Manhattan, with the well known token 89c25
+    let nyc = Coord {
+        x: -74.0060,
+        y: 40.7128,
+    };
+    assert_eq!(s2_cell_id(nyc, 8).unwrap(), 0x89c25 << 44);
+
+    // the cells of a coordinate at each level are nested
+    let mut seed = 3;
+    for _ in 0..500 {
+        let c = Coord {
+            x: next(&mut seed) * 360.0 - 180.0,
+            y: next(&mut seed) * 180.0 - 90.0,
+        };
+        let mut id = s2_cell_id(c, 30).unwrap();
+        assert_eq!(id & 1, 1);
+        for level in (0..30).rev() {
+            id = parent(id);
+            assert_eq!(s2_cell_id(c, level).unwrap(), id, "{:?} {}", c, level);
+        }
+    }
+
+    assert!(s2_cell_id(nyc, 31).is_err());
+    assert!(s2_cell_id(Coord { x: 0.0, y: 95.0 }, 3).is_err());
+}
+
+#[test]
+fn test_rect_to_s2_cells() {
+    let mut seed = 11;
+    for (len, level) in [(2, 4), (4, 9), (6, 14), (8, 20)] {
+        for _ in 0..100 {
+            let c = Coord {
+                x: next(&mut seed) * 360.0 - 180.0,
+                y: next(&mut seed) * 180.0 - 90.0,
+            };
+            let hash = encode(c, len).unwrap();
+            let cells = hash_to_s2_cells(&hash, level).unwrap();
+            assert!(cells.windows(2).all(|w| w[0] < w[1]));
+
+            // every point of the geohash lies in one of the cells
+            let rect = decode_bbox(&hash).unwrap();
+            for _ in 0..20 {
+                let p = Coord {
+                    x: rect.min().x + next(&mut seed) * rect.width(),
+                    y: rect.min().y + next(&mut seed) * rect.height(),
+                };
+                let id = s2_cell_id(p, level).unwrap();
+                assert!(cells.binary_search(&id).is_ok(), "{} {:?}", hash, p);
+            }
+        }
+    }
+
+    // a point is covered by its own cell
+    let point = Coord { x: 2.35, y: 48.85 };
+    let cells = rect_to_s2_cells(Rect::new(point, point), 12).unwrap();
+    assert!(cells.contains(&s2_cell_id(point, 12).unwrap()));
+
+    let world = Rect::new(
+        Coord {
+            x: -180.0,
+            y: -90.0,
+        },
+        Coord { x: 180.0, y: 90.0 },
+    );
+    let faces = rect_to_s2_cells(world, 0).unwrap();
+    assert_eq!(faces, (0..6).map(|f| f << 61 | 1 << 60).collect::<Vec<_>>());
+    assert_eq!(rect_to_s2_cells(world, 2).unwrap().len(), 6 * 16);
+    assert!(rect_to_s2_cells(world, 31).is_err());
+}
+
+#[test]
+fn test_s2_cell_to_hashes() {
+    let mut seed = 5;
+    for (level, len) in [(3, 2), (8, 4), (13, 6), (18, 7)] {
+        for _ in 0..100 {
+            // include points around the poles and the antimeridian
+            let c = Coord {
+                x: next(&mut seed) * 360.0 - 180.0,
+                y: (next(&mut seed) * 2.0 - 1.0) * 90.0,
+            };
+            let id = s2_cell_id(c, level).unwrap();
+            let hashes = s2_cell_to_hashes(id, len).unwrap();
+            assert!(hashes.windows(2).all(|w| w[0] < w[1]));
+            assert!(
+                hashes.contains(&encode(c, len).unwrap()),
+                "{:x} {:?}",
+                id,
+                c
+            );
+        }
+    }
+    for c in [
+        Coord { x: 0.0, y: 89.99 },
+        Coord { x: 45.0, y: -89.99 },
+        Coord { x: 179.99, y: 10.0 },
+        Coord {
+            x: -180.0,
+            y: -10.0,
+        },
+    ] {
+        let id = s2_cell_id(c, 6).unwrap();
+        assert!(s2_cell_to_hashes(id, 3)
+            .unwrap()
+            .contains(&encode(c, 3).unwrap()));
+    }
+
+    for id in [0, 0xd << 60, 0x89c25 << 43, 1 << 62] {
+        assert!(s2_cell_to_hashes(id, 3).is_err(), "{:x}", id);
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "rand")]
+
+use geohash::{decode_bbox, sample_point, sample_points, CellSet};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn test_sample_point() {
+    let mut rng = StdRng::seed_from_u64(71);
+    // a cell from 45 degrees to the pole, much wider near its southern edge
+    let rect = decode_bbox("g").unwrap();
+    let (lo, hi) = (rect.min().y, rect.max().y);
+    let mid = (lo + hi) / 2.0;
+    let n = 20_000;
+    let mut below = 0;
+    for _ in 0..n {
+        let c = sample_point("g", &mut rng).unwrap();
+        assert!(rect.min().x <= c.x && c.x <= rect.max().x);
+        assert!(lo <= c.y && c.y <= hi);
+        if c.y < mid {
+            below += 1;
+        }
+    }
+    let sin = |y: f64| y.to_radians().sin();
+    let expected = (sin(mid) - sin(lo)) / (sin(hi) - sin(lo));
+    assert!((below as f64 / n as f64 - expected).abs() < 0.01);
+
+    // the same seed gives the same points
+    let draw = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..10)
+            .map(|_| sample_point("u09tun", &mut rng).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(draw(3), draw(3));
+    assert_ne!(draw(3), draw(4));
+    assert!(sample_point("u0a", &mut rng).is_err());
+}
+
+#[test]
+fn test_sample_points() {
+    let mut rng = StdRng::seed_from_u64(73);
+    // cells of the same length at the equator and close to the pole
+    let equator = CellSet::from_hashes(["s00"]).unwrap();
+    let polar = CellSet::from_hashes(["upb"]).unwrap();
+    let set = equator.union(&polar);
+
+    let n = 20_000;
+    let points = sample_points(&set, n, &mut rng);
+    assert_eq!(points.len(), n);
+    assert!(points.iter().all(|&c| set.contains_point(c)));
+
+    // monte-carlo estimate of the share of the area of each cell
+    let share = points.iter().filter(|&&c| polar.contains_point(c)).count() as f64 / n as f64;
+    let expected = polar.area_km2() / set.area_km2();
+    assert!(expected < 0.1);
+    assert!((share - expected).abs() < 0.01);
+
+    assert_eq!(
+        sample_points(&set, 5, &mut StdRng::seed_from_u64(1)),
+        sample_points(&set, 5, &mut StdRng::seed_from_u64(1))
+    );
+    assert!(sample_points(&CellSet::new(), 5, &mut rng).is_empty());
+}
@@ -0,0 +1,98 @@
+use geohash::{encode, from_sort_key, sort_key_prefix, to_sort_key, CompositeKey, Coord};
+
+#[test]
+fn test_sort_key_order() {
+    // byte order of the keys matches the order of the hashes, parents first
+    let mut hashes = vec![
+        "9q60y60rhs",
+        "9q60y",
+        "9q6",
+        "9q7",
+        "ezs42",
+        "0",
+        "zzzzzzzzzzzz",
+        "9q60y0",
+        "s",
+    ];
+    let mut keys: Vec<_> = hashes.iter().map(|h| to_sort_key(h).unwrap()).collect();
+    hashes.sort();
+    keys.sort();
+    for (hash, key) in hashes.iter().zip(&keys) {
+        let (decoded, consumed) = from_sort_key(key).unwrap();
+        assert_eq!(&decoded, hash);
+        assert_eq!(consumed, key.len());
+    }
+
+    // integer hashes, left aligned, sort in the same order
+    let mut ints: Vec<_> = hashes
+        .iter()
+        .map(|h| (geohash::decode_bbox(h).unwrap().min(), h.len()))
+        .map(|(c, len)| (geohash::encode_int(c, 60).unwrap(), len))
+        .collect();
+    let sorted = ints.clone();
+    ints.sort();
+    assert_eq!(ints, sorted);
+
+    assert!(from_sort_key(&[]).is_err());
+    assert!(from_sort_key(&[0]).is_err());
+    assert!(from_sort_key(&[1, 2, 3]).is_err());
+    assert!(from_sort_key(&[1, 33, 0]).is_err());
+    assert!(to_sort_key("abc").is_err());
+}
+
+#[test]
+fn test_composite_key_prefix_scan() {
+    let points = [
+        (
+            Coord {
+                x: -120.6623,
+                y: 35.3003,
+            },
+            3u64,
+            "a",
+        ),
+        (
+            Coord {
+                x: -120.6623,
+                y: 35.3003,
+            },
+            1u64,
+            "b",
+        ),
+        (
+            Coord {
+                x: -120.66,
+                y: 35.30,
+            },
+            2u64,
+            "c",
+        ),
+        (Coord { x: -120.0, y: 35.0 }, 5u64, "d"),
+        (Coord { x: 10.0, y: 50.0 }, 0u64, "e"),
+    ];
+    let mut keys: Vec<_> = points
+        .iter()
+        .map(|(c, ts, id)| {
+            CompositeKey {
+                geohash: encode(*c, 12).unwrap(),
+                timestamp: *ts,
+                id: id.as_bytes().to_vec(),
+            }
+            .to_bytes()
+            .unwrap()
+        })
+        .collect();
+    keys.sort();
+
+    let prefix = sort_key_prefix(&encode(points[0].0, 6).unwrap()).unwrap();
+    let ids: Vec<_> = keys
+        .iter()
+        .filter(|k| k.starts_with(&prefix))
+        .map(|k| CompositeKey::from_bytes(k).unwrap())
+        .map(|k| String::from_utf8(k.id).unwrap())
+        .collect();
+    // identical geohashes are ordered by timestamp
+    assert_eq!(ids, ["b", "a", "c"]);
+
+    assert!(CompositeKey::from_bytes(&to_sort_key("9q6").unwrap()).is_err());
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use common::next;
+use geohash::{decode_bbox, encode, Coord, KeyLayout, Rect, SpaceTimeKey};
+
+const ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+// the time bucket written in `len` characters
+fn bucket_chars(index: u64, len: usize) -> String {
+    (0..len)
+        .rev()
+        .map(|i| ALPHABET[(index >> (5 * i)) as usize & 0x1f] as char)
+        .collect()
+}
+
+#[test]
+fn test_encode_decode() {
+    let mut seed = 47;
+    for &(geohash_len, bucket, time_len) in
+        [(6, 3600, 4), (3, 60, 7), (8, 86400, 3), (1, 1, 11)].iter()
+    {
+        let concatenated =
+            SpaceTimeKey::new(KeyLayout::Concatenated, geohash_len, bucket, time_len).unwrap();
+        let interleaved =
+            SpaceTimeKey::new(KeyLayout::Interleaved, geohash_len, bucket, time_len).unwrap();
+        assert_eq!(concatenated.key_len(), geohash_len + time_len);
+        for _ in 0..500 {
+            let c = Coord {
+                x: next(&mut seed) * 359.0 - 179.5,
+                y: next(&mut seed) * 179.0 - 89.5,
+            };
+            let timestamp = (next(&mut seed) * 2e9) as u64;
+            let hash = encode(c, geohash_len).unwrap();
+            let time = bucket_chars(timestamp / bucket, time_len);
+
+            let key = concatenated.encode(c, timestamp).unwrap();
+            assert_eq!(key, format!("{}{}", hash, time));
+
+            // characters alternate, then the rest of the longer part follows
+            let mut expected = String::new();
+            for i in 0..geohash_len.max(time_len) {
+                expected.extend(hash.chars().nth(i));
+                expected.extend(time.chars().nth(i));
+            }
+            assert_eq!(interleaved.encode(c, timestamp).unwrap(), expected);
+
+            for scheme in [concatenated, interleaved] {
+                let key = scheme.encode(c, timestamp).unwrap();
+                let (rect, range) = scheme.decode(&key).unwrap();
+                assert_eq!(rect, decode_bbox(&hash).unwrap());
+                assert!(range.contains(&timestamp));
+                assert_eq!(range.end - range.start, bucket);
+
+                let int_key = scheme.encode_int(c, timestamp).unwrap();
+                assert_eq!(scheme.int_to_key(int_key), key);
+                assert_eq!(scheme.decode_int(int_key).unwrap(), (rect, range));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rect_to_ranges() {
+    let mut seed = 53;
+    for layout in [KeyLayout::Interleaved, KeyLayout::Concatenated] {
+        let scheme = SpaceTimeKey::new(layout, 7, 600, 5).unwrap();
+        for _ in 0..40 {
+            let (x, y) = (
+                next(&mut seed) * 300.0 - 150.0,
+                next(&mut seed) * 140.0 - 70.0,
+            );
+            let (w, h) = (next(&mut seed) * 0.5, next(&mut seed) * 0.5);
+            let rect = Rect::new(Coord { x, y }, Coord { x: x + w, y: y + h });
+            let start = 1_600_000_000 + (next(&mut seed) * 1e8) as u64;
+            let time = start..start + (next(&mut seed) * 1e6) as u64 + 1;
+
+            for max_ranges in [1, 8, 64] {
+                let ranges = scheme
+                    .rect_to_ranges(rect, time.clone(), max_ranges)
+                    .unwrap();
+                assert!(!ranges.is_empty() && ranges.len() <= max_ranges);
+                assert!(ranges.windows(2).all(|w| w[0].end() + 1 < *w[1].start()));
+                for _ in 0..200 {
+                    let c = Coord {
+                        x: x + next(&mut seed) * w,
+                        y: y + next(&mut seed) * h,
+                    };
+                    let t = time.start + (next(&mut seed) * (time.end - time.start) as f64) as u64;
+                    let key = scheme.encode_int(c, t).unwrap();
+                    assert!(ranges.iter().any(|r| r.contains(&key)), "{:?} {}", c, t);
+                }
+            }
+        }
+    }
+
+    // without a limit, the ranges hold exactly the keys of the cells and buckets overlapping
+    // the query
+    let scheme = SpaceTimeKey::new(KeyLayout::Interleaved, 2, 3600, 1).unwrap();
+    let rect = Rect::new(Coord { x: 10.0, y: 10.0 }, Coord { x: 40.0, y: 30.0 });
+    let time = 5000..20000;
+    let ranges = scheme
+        .rect_to_ranges(rect, time.clone(), usize::MAX)
+        .unwrap();
+    for int_key in 0..1 << scheme.bits() {
+        let (cell, bucket) = scheme.decode_int(int_key).unwrap();
+        let overlaps = cell.min().x < rect.max().x
+            && rect.min().x < cell.max().x
+            && cell.min().y < rect.max().y
+            && rect.min().y < cell.max().y
+            && bucket.start < time.end
+            && time.start < bucket.end;
+        assert_eq!(
+            ranges.iter().any(|r| r.contains(&int_key)),
+            overlaps,
+            "{:?} {:?}",
+            cell,
+            bucket
+        );
+    }
+    assert!(scheme.rect_to_ranges(rect, 10..10, 4).unwrap().is_empty());
+}
+
+#[test]
+fn test_invalid_keys() {
+    // the errors tell which part of the scheme is wrong
+    for (geohash_len, time_len, message) in [
+        (0, 5, "geohash length 0, must be at least 1"),
+        (6, 0, "time length 0, must be at least 1"),
+        (
+            8,
+            5,
+            "geohash length 8 and time length 5 add up to more than 12",
+        ),
+    ] {
+        let err = SpaceTimeKey::new(KeyLayout::Interleaved, geohash_len, 60, time_len);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            format!("Invalid space-time key: {}", message)
+        );
+    }
+    assert!(SpaceTimeKey::new(KeyLayout::Interleaved, 6, 0, 4).is_err());
+
+    // the end of the last bucket must not overflow, so that decoded buckets contain the
+    // encoded timestamps
+    assert!(SpaceTimeKey::new(KeyLayout::Interleaved, 6, u64::MAX, 1).is_err());
+    let largest = u64::MAX >> 5;
+    assert!(SpaceTimeKey::new(KeyLayout::Interleaved, 6, largest + 1, 1).is_err());
+    let scheme = SpaceTimeKey::new(KeyLayout::Interleaved, 6, largest, 1).unwrap();
+    let timestamp = u64::MAX - 100;
+    let key = scheme
+        .encode_int(Coord { x: 2.35, y: 48.85 }, timestamp)
+        .unwrap();
+    assert!(scheme.decode_int(key).unwrap().1.contains(&timestamp));
+
+    let scheme = SpaceTimeKey::new(KeyLayout::Concatenated, 6, 3600, 2).unwrap();
+    let c = Coord { x: 2.35, y: 48.85 };
+    assert!(scheme.encode(c, 1024 * 3600 - 1).is_ok());
+    assert!(scheme.encode(c, 1024 * 3600).is_err());
+    assert!(scheme.encode(Coord { x: 181.0, y: 0.0 }, 0).is_err());
+    assert!(scheme.decode("u09tun0").is_err());
+    assert!(scheme.decode("u09tun0a").is_err());
+    let rect = Rect::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 0.0, y: 95.0 });
+    assert!(scheme.rect_to_ranges(rect, 0..10, 4).is_err());
+}
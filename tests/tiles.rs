@@ -0,0 +1,95 @@
+use geohash::{
+    cover_rect, decode_bbox, hash_to_tile, hash_to_tiles, tile_to_hashes, Coord, Format,
+    GeohashError, Rect, Tile, MERCATOR_MAX_LAT,
+};
+
+fn overlaps(a: Rect<f64>, b: Rect<f64>) -> bool {
+    a.min().x < b.max().x && b.min().x < a.max().x && a.min().y < b.max().y && b.min().y < a.max().y
+}
+
+#[test]
+fn test_quadkeys() {
+    for quadkey in [
+        "",
+        "0",
+        "3",
+        "213",
+        "1202102332221212",
+        "033333333333333333333333333333",
+    ] {
+        let tile = Tile::from_quadkey(quadkey).unwrap();
+        assert_eq!(tile.quadkey(), quadkey);
+        assert_eq!(tile.z as usize, quadkey.len());
+        assert_eq!(Tile::new(tile.x, tile.y, tile.z).unwrap(), tile);
+    }
+    let err = Tile::from_quadkey("124").unwrap_err();
+    assert!(matches!(
+        err,
+        GeohashError::InvalidFormat {
+            format: Format::Tile,
+            ..
+        }
+    ));
+    assert_eq!(err.to_string(), "Invalid tile: 124");
+    assert!(Tile::from_quadkey("0000000000000000000000000000000").is_err());
+    assert!(Tile::new(4, 0, 2).is_err());
+    assert!(Tile::new(0, 0, 31).is_err());
+
+    let world = Tile::new(0, 0, 0).unwrap().bbox();
+    assert!((world.max().y - MERCATOR_MAX_LAT).abs() < 1e-9);
+    assert!((world.min().y + MERCATOR_MAX_LAT).abs() < 1e-9);
+    assert_eq!(world.min().x, -180.0);
+    assert_eq!(world.max().x, 180.0);
+}
+
+#[test]
+fn test_hash_to_tiles() {
+    for hash in ["9q8yy", "u4pruyd", "s0000", "gcpvj", "b", "kz"] {
+        let cell = decode_bbox(hash).unwrap();
+        // keep to zoom levels where the geohash is covered by a handful of tiles
+        let max_zoom = 2 * hash.len() as u8 + 6;
+        for zoom in (0..=max_zoom).step_by(3) {
+            let tiles = hash_to_tiles(hash, zoom).unwrap();
+            assert!(!tiles.is_empty(), "{} at zoom {}", hash, zoom);
+            // every tile overlaps the geohash, and together they cover it
+            let (mut min, mut max) = (tiles[0].bbox().min(), tiles[0].bbox().max());
+            for tile in &tiles {
+                assert_eq!(tile.z, zoom);
+                assert!(overlaps(tile.bbox(), cell), "{} {}", hash, tile);
+                min = Coord {
+                    x: min.x.min(tile.bbox().min().x),
+                    y: min.y.min(tile.bbox().min().y),
+                };
+                max = Coord {
+                    x: max.x.max(tile.bbox().max().x),
+                    y: max.y.max(tile.bbox().max().y),
+                };
+            }
+            assert!(min.x <= cell.min().x && max.x >= cell.max().x);
+            assert!(min.y <= cell.min().y && max.y >= cell.max().y.min(MERCATOR_MAX_LAT));
+        }
+
+        let tile = hash_to_tile(hash).unwrap().unwrap();
+        assert_eq!(hash_to_tiles(hash, tile.z).unwrap(), [tile]);
+        assert!(hash_to_tiles(hash, tile.z + 1).unwrap().len() > 1);
+    }
+
+    // geohash cells past the latitude limit have no tiles
+    assert!(hash_to_tiles("zzzz", 5).unwrap().is_empty());
+    assert_eq!(hash_to_tile("zzzz").unwrap(), None);
+    assert!(hash_to_tiles("9q8", 31).is_err());
+}
+
+#[test]
+fn test_tile_to_hashes() {
+    let tile = Tile::from_quadkey("0230102").unwrap();
+    let hashes = tile_to_hashes(tile, 5).unwrap();
+    assert_eq!(hashes, cover_rect(tile.bbox(), 5).unwrap());
+    for hash in &hashes {
+        assert!(overlaps(decode_bbox(hash).unwrap(), tile.bbox()));
+    }
+    // and back again, the tile is among the tiles of every covering geohash
+    for hash in &hashes {
+        assert!(hash_to_tiles(hash, tile.z).unwrap().contains(&tile));
+    }
+}
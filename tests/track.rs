@@ -0,0 +1,97 @@
+mod common;
+
+use common::next;
+use geohash::{decode, decode_track, encode, encode_track, Coord};
+
+// a random walk, wrapping around the antimeridian and staying away from the poles
+fn walk(seed: &mut u64, start: Coord<f64>, step: f64, points: usize) -> Vec<Coord<f64>> {
+    let mut c = start;
+    (0..points)
+        .map(|_| {
+            c.x += (next(seed) * 2.0 - 1.0) * step;
+            c.y = (c.y + (next(seed) * 2.0 - 1.0) * step).clamp(-89.0, 89.0);
+            if c.x >= 180.0 {
+                c.x -= 360.0;
+            } else if c.x < -180.0 {
+                c.x += 360.0;
+            }
+            c
+        })
+        .collect()
+}
+
+#[test]
+fn test_round_trip() {
+    let mut seed = 71;
+    for &(len, step) in [(1, 30.0), (5, 0.1), (8, 0.001), (12, 1e-6), (6, 90.0)].iter() {
+        for _ in 0..50 {
+            let start = Coord {
+                x: next(&mut seed) * 359.0 - 179.5,
+                y: next(&mut seed) * 170.0 - 85.0,
+            };
+            let track = walk(&mut seed, start, step, 100);
+            let bytes = encode_track(track.iter().copied(), len).unwrap();
+            let decoded = decode_track(&bytes).unwrap();
+            assert_eq!(decoded.len(), track.len());
+
+            // every point decodes to the center of its geohash
+            for (c, d) in track.iter().zip(decoded.iter()) {
+                let (center, lon_err, lat_err) = decode(&encode(*c, len).unwrap()).unwrap();
+                assert_eq!(*d, center);
+                assert!((c.x - d.x).abs() <= lon_err && (c.y - d.y).abs() <= lat_err);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_compact() {
+    // a vehicle moving a few meters per fix needs two bytes per point
+    let mut seed = 73;
+    let track = walk(&mut seed, Coord { x: 2.35, y: 48.85 }, 1e-4, 1000);
+    let bytes = encode_track(track.iter().copied(), 8).unwrap();
+    assert_eq!(bytes[0], 8);
+    assert_eq!(&bytes[1..9], encode(track[0], 8).unwrap().as_bytes());
+    assert_eq!(bytes.len(), 9 + 999 * 2);
+
+    // crossing the antimeridian takes a single column
+    let crossing = [Coord { x: 179.99, y: 0.0 }, Coord { x: -179.99, y: 0.0 }];
+    let bytes = encode_track(crossing.iter().copied(), 5).unwrap();
+    assert_eq!(&bytes[6..], [2, 0]);
+
+    assert_eq!(encode_track(Vec::new(), 7).unwrap(), [7]);
+    assert!(decode_track(&[7]).unwrap().is_empty());
+}
+
+#[test]
+fn test_invalid_tracks() {
+    assert!(encode_track(vec![Coord { x: 0.0, y: 0.0 }], 0).is_err());
+    assert!(encode_track(vec![Coord { x: 0.0, y: 0.0 }], 13).is_err());
+    assert!(encode_track(vec![Coord { x: 0.0, y: 91.0 }], 5).is_err());
+
+    let bytes = encode_track(vec![Coord { x: 0.0, y: 0.0 }, Coord { x: 1.0, y: 1.0 }], 5).unwrap();
+    assert!(decode_track(&bytes[..bytes.len() - 1]).is_err());
+    assert!(decode_track(&bytes[..4]).is_err());
+    assert!(decode_track(&[]).is_err());
+    assert!(decode_track(&[13]).is_err());
+    assert!(decode_track(b"\x02a0").is_err());
+    // far north of the grid
+    assert!(decode_track(b"\x02s0\x00\x80\x01").is_err());
+    assert!(decode_track(&[
+        2, b's', b'0', 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01
+    ])
+    .is_err());
+    // the tenth byte of an offset can only hold one more bit
+    let overflow = decode_track(&[
+        2, b's', b'0', 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02, 0,
+    ]);
+    assert_eq!(
+        overflow.unwrap_err().to_string(),
+        "Invalid track: offset overflows 64 bits"
+    );
+    let not_ascii = decode_track("\x02\u{e9}".as_bytes());
+    assert_eq!(
+        not_ascii.unwrap_err().to_string(),
+        "Invalid track: first geohash is not ASCII"
+    );
+}
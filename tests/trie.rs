@@ -0,0 +1,98 @@
+use geohash::{encode, Coord, GeohashTrie};
+
+#[test]
+fn test_trie_insert_get_remove() {
+    let mut trie = GeohashTrie::new();
+    assert_eq!(trie.insert("ezs42", 1).unwrap(), None);
+    assert_eq!(trie.insert("ezs42", 2).unwrap(), Some(1));
+    assert_eq!(trie.insert("ezs4", 3).unwrap(), None);
+    assert_eq!(trie.len(), 2);
+    assert!(trie.insert("ezsa", 4).is_err());
+    assert!(trie.insert("", 4).is_err());
+
+    assert_eq!(trie.get("ezs42"), Some(&2));
+    assert_eq!(trie.get("ezs"), None);
+    assert_eq!(trie.get("ezs421"), None);
+    *trie.get_mut("ezs4").unwrap() += 10;
+    assert_eq!(trie.get("ezs4"), Some(&13));
+
+    let c = Coord { x: -5.6, y: 42.6 };
+    let hash = encode(c, 7).unwrap();
+    assert_eq!(trie.insert_point(c, 7, 5).unwrap(), None);
+    assert_eq!(trie.get(&hash), Some(&5));
+    assert!(trie.insert_point(c, 13, 5).is_err());
+
+    assert_eq!(trie.remove("ezs4"), Some(13));
+    assert_eq!(trie.remove("ezs4"), None);
+    assert_eq!(trie.get("ezs42"), Some(&2));
+    assert_eq!(trie.remove(&hash), Some(5));
+    assert_eq!(trie.remove("ezs42"), Some(2));
+    assert!(trie.is_empty());
+    assert_eq!(trie.iter().count(), 0);
+}
+
+#[test]
+fn test_trie_prefix_and_rollup() {
+    let mut trie = GeohashTrie::new();
+    let points = [
+        Coord { x: 13.40, y: 52.52 },
+        Coord { x: 13.41, y: 52.53 },
+        Coord { x: 13.38, y: 52.51 },
+        Coord { x: 2.35, y: 48.85 },
+    ];
+    for c in points.iter() {
+        let count = trie
+            .get_or_insert_with(&encode(*c, 8).unwrap(), || 0)
+            .unwrap();
+        *count += 1;
+    }
+
+    let berlin = encode(points[0], 4).unwrap();
+    assert_eq!(trie.get_prefix(&berlin).len(), 3);
+    for (hash, _) in trie.get_prefix(&berlin) {
+        assert!(hash.starts_with(&berlin));
+    }
+    assert!(trie.get_prefix("7").is_empty());
+
+    let keys: Vec<_> = trie.iter().map(|(hash, _)| hash).collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys, sorted);
+
+    let rolled = trie.rollup(3, 0, |sum, count| sum + count);
+    assert_eq!(rolled.len(), 2);
+    assert_eq!(rolled.get(&berlin[..3]), Some(&3));
+    assert_eq!(rolled.get(&encode(points[3], 3).unwrap()), Some(&1));
+}
+
+#[test]
+fn test_trie_longest_match() {
+    let mut zones = GeohashTrie::new();
+    zones.insert("dr5", "new york").unwrap();
+    zones.insert("dr5r", "manhattan").unwrap();
+    zones.insert("dr5ru", "midtown").unwrap();
+
+    assert_eq!(
+        zones.longest_match("dr5ru7c"),
+        Some(("dr5ru".to_owned(), &"midtown"))
+    );
+    assert_eq!(
+        zones.longest_match("dr5rs"),
+        Some(("dr5r".to_owned(), &"manhattan"))
+    );
+    assert_eq!(zones.longest_match("dr4"), None);
+    assert_eq!(
+        zones.longest_match("dr5"),
+        Some(("dr5".to_owned(), &"new york"))
+    );
+
+    let times_square = Coord {
+        x: -73.9855,
+        y: 40.7580,
+    };
+    assert_eq!(
+        zones.longest_match_point(times_square),
+        Some(("dr5ru".to_owned(), &"midtown"))
+    );
+    assert_eq!(zones.longest_match_point(Coord { x: 0.0, y: 0.0 }), None);
+}
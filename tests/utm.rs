@@ -0,0 +1,159 @@
+mod common;
+
+use common::next;
+use geohash::{
+    decode_bbox, decode_mgrs, encode_mgrs, hash_to_mgrs, hash_to_utm, mgrs_to_hashes, Coord, Utm,
+};
+
+#[test]
+fn test_published_points() {
+    // Eiffel Tower, and the origin of the grid, from Chris Veness' geodesy library
+    let utm = Utm::from_coord(Coord {
+        x: 2.2945,
+        y: 48.8582,
+    })
+    .unwrap();
+    assert_eq!((utm.zone, utm.band), (31, 'U'));
+    assert!((utm.easting - 448251.795).abs() < 1e-3);
+    assert!((utm.northing - 5411932.678).abs() < 1e-3);
+    assert_eq!(
+        encode_mgrs(
+            Coord {
+                x: 2.2945,
+                y: 48.8582
+            },
+            5
+        )
+        .unwrap(),
+        "31UDQ4825111932"
+    );
+
+    let utm = Utm::from_coord(Coord { x: 0.0, y: 0.0 }).unwrap();
+    assert_eq!(utm.to_string(), "31N 166021 0");
+    assert!((utm.easting - 166021.443).abs() < 1e-3);
+    assert_eq!(
+        encode_mgrs(Coord { x: 0.0, y: 0.0 }, 5).unwrap(),
+        "31NAA6602100000"
+    );
+
+    // Baghdad, from the GeographicLib documentation
+    assert_eq!(
+        encode_mgrs(Coord { x: 44.4, y: 33.3 }, 5).unwrap(),
+        "38SMB4414084706"
+    );
+
+    // points on a central meridian have an easting of exactly 500km
+    let utm = Utm::from_coord(Coord { x: -75.0, y: -40.0 }).unwrap();
+    assert_eq!((utm.zone, utm.band), (18, 'H'));
+    assert!((utm.easting - 500000.0).abs() < 1e-6);
+
+    // zone exceptions for southwestern Norway and Svalbard
+    for (x, y, zone, band) in [
+        (5.32, 60.39, 32, 'V'),
+        (3.5, 60.0, 32, 'V'),
+        (2.9, 60.0, 31, 'V'),
+        (3.5, 64.5, 31, 'W'),
+        (15.65, 78.22, 33, 'X'),
+        (8.9, 75.0, 31, 'X'),
+        (9.1, 75.0, 33, 'X'),
+        (32.9, 75.0, 35, 'X'),
+        (41.9, 83.9, 37, 'X'),
+        (42.5, 75.0, 38, 'X'),
+        (180.0, -80.0, 60, 'C'),
+    ] {
+        let utm = Utm::from_coord(Coord { x, y }).unwrap();
+        assert_eq!((utm.zone, utm.band), (zone, band), "{} {}", x, y);
+    }
+
+    assert!(Utm::from_coord(Coord { x: 0.0, y: 84.5 }).is_err());
+    assert!(Utm::from_coord(Coord { x: 0.0, y: -80.5 }).is_err());
+}
+
+#[test]
+fn test_round_trips() {
+    let mut seed = 7;
+    for _ in 0..2000 {
+        let c = Coord {
+            x: next(&mut seed) * 360.0 - 180.0,
+            y: next(&mut seed) * 164.0 - 80.0,
+        };
+        let utm = Utm::from_coord(c).unwrap();
+        let back = utm.to_coord().unwrap();
+        assert!(
+            (back.x - c.x).abs() < 1e-9 && (back.y - c.y).abs() < 1e-9,
+            "{:?}",
+            c
+        );
+
+        for digits in [0, 1, 3, 5] {
+            let reference = encode_mgrs(c, digits).unwrap();
+            let bbox = decode_mgrs(&reference).unwrap();
+            let margin = 1e-9;
+            assert!(
+                bbox.min().x - margin <= c.x && c.x <= bbox.max().x + margin,
+                "{:?} {}",
+                c,
+                reference
+            );
+            assert!(
+                bbox.min().y - margin <= c.y && c.y <= bbox.max().y + margin,
+                "{:?} {}",
+                c,
+                reference
+            );
+            // the center of a square may fall into the next latitude band, with the same square
+            let without_band = |r: &str| r.replacen(|c: char| c.is_ascii_alphabetic(), "", 1);
+            let center = encode_mgrs(bbox.center(), digits).unwrap();
+            assert_eq!(without_band(&center), without_band(&reference));
+        }
+    }
+
+    let bad = Utm {
+        zone: 61,
+        band: 'U',
+        easting: 500000.0,
+        northing: 0.0,
+    };
+    assert!(bad.to_coord().is_err());
+    for reference in [
+        "",
+        "31U",
+        "31UD",
+        "31IDQ",
+        "31UDI",
+        "31UDQ123",
+        "61UDQ",
+        "32XMH",
+        "34XDK",
+        "36XWP",
+        "31ABé",
+        "31UDé",
+        "31UDQ12a4",
+    ] {
+        assert!(decode_mgrs(reference).is_err(), "{}", reference);
+    }
+    assert!(encode_mgrs(Coord { x: 0.0, y: 0.0 }, 6).is_err());
+
+    // the widened zones of Svalbard still decode
+    for lon in [4.5, 15.0, 27.0, 39.0] {
+        let reference = encode_mgrs(Coord { x: lon, y: 78.0 }, 2).unwrap();
+        assert!(decode_mgrs(&reference).is_ok(), "{}", reference);
+    }
+}
+
+#[test]
+fn test_geohash_conversions() {
+    for hash in ["u09tunq", "9q8yyk8", "r3gx2f9", "6gkzmg1"] {
+        let cell = decode_bbox(hash).unwrap();
+        let reference = hash_to_mgrs(hash, 4).unwrap();
+        assert_eq!(reference, encode_mgrs(cell.center(), 4).unwrap());
+        let utm = hash_to_utm(hash).unwrap();
+        let center = utm.to_coord().unwrap();
+        assert!((center.x - cell.center().x).abs() < 1e-9);
+        assert!((center.y - cell.center().y).abs() < 1e-9);
+
+        // the geohash center is covered by the geohashes of its grid square
+        let hashes = mgrs_to_hashes(&reference, hash.len()).unwrap();
+        assert!(hashes.contains(&hash.to_owned()), "{} {}", hash, reference);
+    }
+}